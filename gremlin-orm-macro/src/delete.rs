@@ -1,6 +1,10 @@
 use proc_macro2::TokenStream;
 
-use crate::EntityCtx;
+use crate::{
+    EntityCtx,
+    relation::option_inner,
+    soft_delete::{SoftDeleteCtx, SoftDeleteKind},
+};
 
 pub(crate) fn generate_delete(args: &EntityCtx) -> TokenStream {
     let ident = args.ident.clone();
@@ -8,14 +12,19 @@ pub(crate) fn generate_delete(args: &EntityCtx) -> TokenStream {
 
     let base = args.pks().cloned().collect::<Vec<_>>();
 
-    let query_where = base
+    // Without primary keys a single row can not be targeted, which would delete the whole table
+    if base.is_empty() {
+        return TokenStream::new();
+    }
+
+    let mut query_where = base
         .iter()
         .cloned()
         .enumerate()
         .map(|(idx, field)| {
-            let ident = field.ident;
+            let column = field.column_sql();
             let idx = idx + 1;
-            format!("{ident} = ${idx}")
+            format!("{column} = ${idx}")
         })
         .collect::<Vec<_>>();
 
@@ -28,9 +37,15 @@ pub(crate) fn generate_delete(args: &EntityCtx) -> TokenStream {
         })
         .collect::<Vec<_>>();
 
-    let query = if let Some(soft_delete_column) = &args.soft_delete {
+    // Soft deleted rows are not deleted again, so only the first delete reports a deleted row
+    if let Some(soft_delete) = &args.soft_delete {
+        query_where.push(soft_delete.filter_sql());
+    }
+
+    let query = if let Some(soft_delete) = &args.soft_delete {
         format!(
-            "UPDATE {table} SET {soft_delete_column} = NOW() WHERE {query_where}",
+            "UPDATE {table} SET {set} WHERE {query_where}",
+            set = soft_delete.delete_sql(),
             query_where = query_where.join(" AND "),
         )
     } else {
@@ -40,18 +55,235 @@ pub(crate) fn generate_delete(args: &EntityCtx) -> TokenStream {
         )
     };
 
-    let stream = quote::quote! {
-        impl ::gremlin_orm::DeletableEntity for #ident {
-            async fn delete<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<(), sqlx::Error> {
+    let columns = if args.checked {
+        args.columns().collect::<Vec<_>>()
+    } else {
+        args.runtime_columns().collect::<Vec<_>>()
+    };
+
+    let returning_query = format!("{query} RETURNING {columns}", columns = columns.join(", "));
+
+    let (delete, delete_returning) = if args.checked {
+        (
+            quote::quote! {
                 ::sqlx::query!(
                     #query,
                     #(#values_fields),*
-                ).execute(executor).await?;
+                ).execute(executor).await?
+            },
+            quote::quote! {
+                ::sqlx::query_as!(
+                    Self,
+                    #returning_query,
+                    #(#values_fields),*
+                ).fetch_optional(executor).await?
+            },
+        )
+    } else {
+        let values = base
+            .iter()
+            .map(|field| field.runtime_value())
+            .collect::<Vec<_>>();
 
-                Ok(())
+        (
+            quote::quote! {
+                ::sqlx::query(#query)
+                    #(.bind(#values))*
+                    .execute(executor)
+                    .await?
+            },
+            quote::quote! {
+                ::sqlx::query_as::<_, Self>(#returning_query)
+                    #(.bind(#values))*
+                    .fetch_optional(executor)
+                    .await?
+            },
+        )
+    };
+
+    let stream = quote::quote! {
+        impl ::gremlin_orm::DeletableEntity for #ident {
+            async fn try_delete<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<bool, ::gremlin_orm::Error> {
+                Ok(#delete.rows_affected() > 0)
+            }
+
+            async fn delete_returning<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Option<Self>, ::gremlin_orm::Error> {
+                Ok(#delete_returning)
             }
         }
     };
 
     stream
 }
+
+pub(crate) fn generate_soft_delete(args: &EntityCtx) -> TokenStream {
+    let Some(soft_delete) = &args.soft_delete else {
+        return TokenStream::new();
+    };
+
+    let base = args.pks().cloned().collect::<Vec<_>>();
+
+    if base.is_empty() {
+        return TokenStream::new();
+    }
+
+    let ident = args.ident.clone();
+    let table = args.table.clone();
+
+    let query_where = base
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let column = field.column_sql();
+            let idx = idx + 1;
+            format!("{column} = ${idx}")
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ");
+
+    let columns = if args.checked {
+        args.columns().collect::<Vec<_>>()
+    } else {
+        args.runtime_columns().collect::<Vec<_>>()
+    };
+
+    let restore_query = format!(
+        "UPDATE {table} SET {restore} WHERE {query_where} AND {deleted} RETURNING {columns}",
+        restore = soft_delete.restore_sql(),
+        deleted = soft_delete.deleted_sql(),
+        columns = columns.join(", ")
+    );
+    let delete_query = format!("DELETE FROM {table} WHERE {query_where}");
+    let purge_query = format!(
+        "DELETE FROM {table} WHERE {deleted}",
+        deleted = soft_delete.deleted_sql()
+    );
+
+    let (restore, delete, purge) = if args.checked {
+        let values = base
+            .iter()
+            .map(|field| {
+                let ident = &field.ident;
+                quote::quote! { self.#ident }
+            })
+            .collect::<Vec<_>>();
+
+        (
+            quote::quote! {
+                ::sqlx::query_as!(Self, #restore_query, #(#values),*)
+                    .fetch_one(executor)
+                    .await?
+            },
+            quote::quote! {
+                ::sqlx::query!(#delete_query, #(#values),*)
+                    .execute(executor)
+                    .await?
+            },
+            quote::quote! {
+                ::sqlx::query!(#purge_query)
+                    .execute(executor)
+                    .await?
+            },
+        )
+    } else {
+        let values = base
+            .iter()
+            .map(|field| field.runtime_value())
+            .collect::<Vec<_>>();
+
+        (
+            quote::quote! {
+                ::sqlx::query_as::<_, Self>(#restore_query)
+                    #(.bind(#values))*
+                    .fetch_one(executor)
+                    .await?
+            },
+            quote::quote! {
+                ::sqlx::query(#delete_query)
+                    #(.bind(#values))*
+                    .execute(executor)
+                    .await?
+            },
+            quote::quote! {
+                ::sqlx::query(#purge_query)
+                    .execute(executor)
+                    .await?
+            },
+        )
+    };
+
+    let purgeable = generate_purgeable(args, soft_delete);
+
+    quote::quote! {
+        impl ::gremlin_orm::SoftDeletableEntity for #ident {
+            async fn restore<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Self, ::gremlin_orm::Error> {
+                Ok(#restore)
+            }
+
+            async fn force_delete<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<(), ::gremlin_orm::Error> {
+                #delete;
+
+                Ok(())
+            }
+
+            async fn purge_deleted<'a>(executor: impl ::sqlx::PgExecutor<'a>) -> Result<u64, ::gremlin_orm::Error> {
+                Ok(#purge.rows_affected())
+            }
+        }
+
+        #purgeable
+    }
+}
+
+/// Entities soft deleted using a timestamp can be purged after a retention period
+fn generate_purgeable(args: &EntityCtx, soft_delete: &SoftDeleteCtx) -> TokenStream {
+    if soft_delete.kind != SoftDeleteKind::Timestamp {
+        return TokenStream::new();
+    }
+
+    // The type of the soft delete column is only known when it is a field of the entity
+    let Some(field) = args
+        .data
+        .iter()
+        .find(|field| field.column == soft_delete.column)
+    else {
+        return TokenStream::new();
+    };
+
+    let ident = args.ident.clone();
+    let table = args.table.clone();
+    let deleted_at = option_inner(&field.ty).unwrap_or(&field.ty);
+
+    let query = format!(
+        "DELETE FROM {table} WHERE {column} < $1",
+        column = soft_delete.column_sql()
+    );
+
+    let purge = if args.checked {
+        quote::quote! {
+            ::sqlx::query!(#query, before)
+                .execute(executor)
+                .await?
+        }
+    } else {
+        quote::quote! {
+            ::sqlx::query(#query)
+                .bind(before)
+                .execute(executor)
+                .await?
+        }
+    };
+
+    quote::quote! {
+        impl ::gremlin_orm::PurgeableEntity for #ident {
+            type DeletedAt = #deleted_at;
+
+            async fn purge_deleted_before<'a>(
+                before: &Self::DeletedAt,
+                executor: impl ::sqlx::PgExecutor<'a>,
+            ) -> Result<u64, ::gremlin_orm::Error> {
+                Ok(#purge.rows_affected())
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use proc_macro2::TokenStream;
+use syn::ext::IdentExt;
 
 use crate::EntityCtx;
 
@@ -24,14 +25,14 @@ pub(crate) fn generate_fetch(args: &EntityCtx) -> TokenStream {
         .cloned()
         .enumerate()
         .map(|(idx, field)| {
-            let ident = field.ident;
+            let column = field.column_sql();
             let idx = idx + 1;
-            format!("{ident} = ${idx}")
+            format!("{column} = ${idx}")
         })
         .collect::<Vec<_>>();
 
     if let Some(soft_delete) = &args.soft_delete {
-        query_where.push(format!("{soft_delete} IS NULL"));
+        query_where.push(soft_delete.filter_sql());
     }
 
     let vis = args.vis.clone();
@@ -41,10 +42,16 @@ pub(crate) fn generate_fetch(args: &EntityCtx) -> TokenStream {
 
     let table = args.table.clone();
 
+    let columns = if args.checked {
+        args.columns().collect::<Vec<_>>()
+    } else {
+        args.runtime_columns().collect::<Vec<_>>()
+    };
+
     let query = format!(
         "SELECT {columns} FROM {table} WHERE {query_where}",
         query_where = query_where.join(" AND "),
-        columns = args.columns().collect::<Vec<_>>().join(", ")
+        columns = columns.join(", ")
     );
 
     let values_fields = base
@@ -71,7 +78,179 @@ pub(crate) fn generate_fetch(args: &EntityCtx) -> TokenStream {
         })
         .collect::<Vec<_>>();
 
+    let fetch = if args.checked {
+        quote::quote! {
+            ::sqlx::query_as!(
+                #source_ident,
+                #query,
+                #(#values_fields),*
+            ).fetch_optional(executor).await?
+        }
+    } else {
+        let values = base.iter().map(|field| field.runtime_value());
+
+        quote::quote! {
+            ::sqlx::query_as::<_, Self::SourceEntity>(#query)
+                #(.bind(#values))*
+                .fetch_optional(executor)
+                .await?
+        }
+    };
+
+    // A single primary key is matched using `= ANY`, while composite keys are matched against the
+    // zipped arrays of their columns. The `= ANY` conditions are kept for composite keys as well,
+    // as they allow PostgreSQL to infer the types of the arrays.
+    let mut many_where = base
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| format!("{} = ANY(${})", field.column_sql(), idx + 1))
+        .collect::<Vec<_>>();
+
+    if base.len() > 1 {
+        many_where.push(format!(
+            "({columns}) IN (SELECT * FROM UNNEST({params}))",
+            columns = base
+                .iter()
+                .map(|field| field.column_sql())
+                .collect::<Vec<_>>()
+                .join(", "),
+            params = (1..=base.len())
+                .map(|idx| format!("${idx}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    if let Some(soft_delete) = &args.soft_delete {
+        many_where.push(soft_delete.filter_sql());
+    }
+
+    let many_query = format!(
+        "SELECT {columns} FROM {table} WHERE {many_where}",
+        many_where = many_where.join(" AND "),
+        columns = columns.join(", ")
+    );
+
+    let many_values = base
+        .iter()
+        .map(|field| quote::format_ident!("{}_values", field.ident.unraw()))
+        .collect::<Vec<_>>();
+
+    let collect_values = base
+        .iter()
+        .zip(&many_values)
+        .map(|(field, values)| {
+            let ident = &field.ident;
+
+            quote::quote! {
+                let #values = keys.iter().map(|key| key.#ident.clone()).collect::<Vec<_>>();
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let fetch_many = if args.checked {
+        quote::quote! {
+            ::sqlx::query_as!(
+                #source_ident,
+                #many_query,
+                #(&#many_values),*
+            ).fetch_all(executor).await?
+        }
+    } else {
+        quote::quote! {
+            ::sqlx::query_as::<_, Self::SourceEntity>(#many_query)
+                #(.bind(&#many_values))*
+                .fetch_all(executor)
+                .await?
+        }
+    };
+
+    let from_ref_fields = base
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+
+            quote::quote! {
+                #ident: value.#ident.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let exists_query = format!(
+        r#"SELECT EXISTS (SELECT 1 FROM {table} WHERE {query_where}) AS "exists!""#,
+        query_where = query_where.join(" AND ")
+    );
+
+    let exists = if args.checked {
+        quote::quote! {
+            ::sqlx::query_scalar!(
+                #exists_query,
+                #(#values_fields),*
+            ).fetch_one(executor).await?
+        }
+    } else {
+        let values = base.iter().map(|field| field.runtime_value());
+
+        quote::quote! {
+            ::sqlx::query_scalar::<_, bool>(#exists_query)
+                #(.bind(#values))*
+                .fetch_one(executor)
+                .await?
+        }
+    };
+
+    // Locking filters the query by the "Pk" struct, which is only possible with primary key(s)
+    let lockable = if base.is_empty() {
+        quote::quote! {}
+    } else {
+        quote::quote! {
+            impl ::gremlin_orm::LockableEntity for #ident {
+                async fn fetch_locked<'a>(&self, lock: ::gremlin_orm::Lock, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Option<Self::SourceEntity>, ::gremlin_orm::Error> {
+                    <Self::SourceEntity as ::gremlin_orm::QueryableEntity>::query()
+                        .filter(self)
+                        .lock(lock)
+                        .fetch_optional(executor)
+                        .await
+                }
+            }
+        }
+    };
+
+    // A single primary key is used as-is, while composite keys are combined into a tuple
+    let key_of = match base.as_slice() {
+        [] => quote::quote! {
+            type Key = ();
+
+            fn key_of(_: &Self::SourceEntity) -> Self::Key {}
+        },
+        [field] => {
+            let ty = &field.ty;
+            let ident = &field.ident;
+
+            quote::quote! {
+                type Key = #ty;
+
+                fn key_of(entity: &Self::SourceEntity) -> Self::Key {
+                    entity.#ident.clone()
+                }
+            }
+        }
+        _ => {
+            let tys = base.iter().map(|field| &field.ty);
+            let idents = base.iter().map(|field| &field.ident);
+
+            quote::quote! {
+                type Key = (#(#tys,)*);
+
+                fn key_of(entity: &Self::SourceEntity) -> Self::Key {
+                    (#(entity.#idents.clone(),)*)
+                }
+            }
+        }
+    };
+
     let stream = quote::quote! {
+        #[derive(Debug, Clone)]
         #vis struct #ident {
             #(#pk_fields),*
         }
@@ -84,17 +263,38 @@ pub(crate) fn generate_fetch(args: &EntityCtx) -> TokenStream {
             }
         }
 
+        impl From<&#source_ident> for #ident {
+            fn from(value: &#source_ident) -> Self {
+                Self {
+                    #(#from_ref_fields),*
+                }
+            }
+        }
+
         impl ::gremlin_orm::FetchableEntity for #ident {
             type SourceEntity = #source_ident;
+            #key_of
+
+            async fn fetch<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Option<Self::SourceEntity>, ::gremlin_orm::Error> {
+                Ok(#fetch)
+            }
+
+            async fn exists<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<bool, ::gremlin_orm::Error> {
+                Ok(#exists)
+            }
+
+            async fn fetch_many<'a>(keys: &[Self], executor: impl ::sqlx::PgExecutor<'a>) -> Result<Vec<Self::SourceEntity>, ::gremlin_orm::Error> {
+                if keys.is_empty() {
+                    return Ok(vec![]);
+                }
 
-            async fn fetch<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Option<Self::SourceEntity>, ::sqlx::Error> {
-                ::sqlx::query_as!(
-                    #source_ident,
-                    #query,
-                    #(#values_fields),*
-                ).fetch_optional(executor).await
+                #(#collect_values)*
+
+                Ok(#fetch_many)
             }
         }
+
+        #lockable
     };
 
     stream
@@ -0,0 +1,173 @@
+use darling::FromMeta;
+
+/// The longest identifier PostgreSQL accepts without truncating it
+const MAX_IDENT_LEN: usize = 63;
+
+/// Quote an identifier, escaping any embedded double quotes
+pub(crate) fn quote_ident(ident: &str) -> String {
+    format!(r#""{}""#, ident.replace('"', r#""""#))
+}
+
+/// Quote an identifier if it can not be used as-is in a query. Unquoted identifiers are folded to
+/// lowercase by PostgreSQL, so anything other than lowercase letters, digits and underscores needs
+/// quoting.
+pub(crate) fn quote_ident_if_required(ident: &str) -> String {
+    let is_plain = ident
+        .chars()
+        .next()
+        .is_some_and(|first| first.is_ascii_lowercase() || first == '_')
+        && ident
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+    if is_plain {
+        ident.to_string()
+    } else {
+        quote_ident(ident)
+    }
+}
+
+/// Quote an identifier, either always or only when required
+pub(crate) fn quote(ident: &str, always: bool) -> String {
+    if always {
+        quote_ident(ident)
+    } else {
+        quote_ident_if_required(ident)
+    }
+}
+
+/// Validate an identifier given as-is, like a column name
+fn validate_ident(ident: &str) -> Result<(), IdentError> {
+    if ident.is_empty() {
+        Err(IdentError::Empty)
+    } else if ident.len() > MAX_IDENT_LEN {
+        Err(IdentError::TooLong(ident.to_string()))
+    } else if ident.contains('\0') {
+        Err(IdentError::InvalidCharacter('\0'))
+    } else {
+        Ok(())
+    }
+}
+
+/// An identifier which is used as-is, like a column or constraint name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Identifier(pub(crate) String);
+
+impl FromMeta for Identifier {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        validate_ident(value).map_err(|err| darling::Error::custom(err.to_string()))?;
+
+        Ok(Self(value.to_string()))
+    }
+}
+
+/// A table name, optionally qualified by a schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TableName {
+    schema: Option<String>,
+    name: String,
+    raw: String,
+}
+
+impl TableName {
+    /// Render the table for usage in a query. When quoting is disabled, the table is used exactly
+    /// as written in the attribute.
+    pub(crate) fn sql(&self, quote: bool) -> String {
+        if !quote {
+            return self.raw.clone();
+        }
+
+        match &self.schema {
+            Some(schema) => format!("{}.{}", quote_ident(schema), quote_ident(&self.name)),
+            None => quote_ident(&self.name),
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, IdentError> {
+        let mut parts = vec![];
+        let mut chars = value.chars().peekable();
+
+        loop {
+            let part = if chars.peek() == Some(&'"') {
+                chars.next();
+
+                let mut part = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            part.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => part.push(c),
+                        None => return Err(IdentError::UnterminatedQuote),
+                    }
+                }
+
+                part
+            } else {
+                let mut part = String::new();
+
+                while let Some(c) = chars.next_if(|c| *c != '.') {
+                    let is_valid = if part.is_empty() {
+                        c.is_alphabetic() || c == '_'
+                    } else {
+                        c.is_alphanumeric() || c == '_' || c == '$'
+                    };
+
+                    if !is_valid {
+                        return Err(IdentError::InvalidCharacter(c));
+                    }
+
+                    part.push(c);
+                }
+
+                // Unquoted identifiers are folded to lowercase by PostgreSQL
+                part.to_lowercase()
+            };
+
+            validate_ident(&part)?;
+            parts.push(part);
+
+            match chars.next() {
+                Some('.') => continue,
+                Some(c) => return Err(IdentError::InvalidCharacter(c)),
+                None => break,
+            }
+        }
+
+        let name = parts.pop().ok_or(IdentError::Empty)?;
+        let schema = parts.pop();
+
+        if !parts.is_empty() {
+            return Err(IdentError::TooManyParts(value.to_string()));
+        }
+
+        Ok(Self {
+            schema,
+            name,
+            raw: value.to_string(),
+        })
+    }
+}
+
+impl FromMeta for TableName {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Self::parse(value).map_err(|err| darling::Error::custom(err.to_string()))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum IdentError {
+    #[error("Identifiers can not be empty")]
+    Empty,
+    #[error("The identifier `{0}` is longer than 63 characters")]
+    TooLong(String),
+    #[error("Unexpected character `{0}` in identifier")]
+    InvalidCharacter(char),
+    #[error("Quoted identifier is not terminated")]
+    UnterminatedQuote,
+    #[error("The table `{0}` should be in the form `table` or `schema.table`")]
+    TooManyParts(String),
+}
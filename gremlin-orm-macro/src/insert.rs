@@ -1,13 +1,32 @@
+use proc_macro_error2::abort;
 use proc_macro2::TokenStream;
 
 use crate::EntityCtx;
 
+/// The maximum number of bind parameters PostgreSQL accepts in a single statement
+const MAX_BIND_PARAMETERS: usize = u16::MAX as usize;
+
 pub(crate) fn generate_insert(args: &EntityCtx) -> TokenStream {
     let insertable_base = args
         .data
         .clone()
         .into_iter()
-        .filter(|field| !field.generated)
+        .filter(|field| !field.generated && !field.is_timestamp())
+        .collect::<Vec<_>>();
+
+    // Timestamp columns are not provided, but set to the current timestamp instead
+    let timestamp_columns = args
+        .data
+        .iter()
+        .filter(|field| field.is_timestamp() && !field.generated)
+        .map(|field| field.column_sql())
+        .collect::<Vec<_>>();
+
+    let updated_at_columns = args
+        .data
+        .iter()
+        .filter(|field| field.updated_at && !field.generated)
+        .map(|field| field.column_sql())
         .collect::<Vec<_>>();
 
     let insertable_fields = insertable_base
@@ -55,17 +74,53 @@ pub(crate) fn generate_insert(args: &EntityCtx) -> TokenStream {
 
     let table = args.table.clone();
 
-    let columns = args
-        .data
-        .iter()
-        .cloned()
-        .map(|field| field.ident.to_string())
-        .collect::<Vec<_>>()
-        .join(", ");
+    let columns = args.runtime_columns().collect::<Vec<_>>().join(", ");
 
     // When no fields that could be inserted are present, use a simplified representation
     if insertable_fields.is_empty() {
-        let query = format!("INSERT INTO {table} DEFAULT VALUES RETURNING {columns}");
+        // Multiple rows of only default values can not be inserted using `DEFAULT VALUES`, so
+        // explicitly use the default for the first column instead
+        let (default_columns, default_row) = if timestamp_columns.is_empty() {
+            (
+                args.data
+                    .first()
+                    .map(|field| field.column_sql())
+                    .unwrap_or_default(),
+                "(DEFAULT)".to_string(),
+            )
+        } else {
+            (
+                timestamp_columns.join(", "),
+                format!("({})", vec!["NOW()"; timestamp_columns.len()].join(", ")),
+            )
+        };
+
+        let default_values = if timestamp_columns.is_empty() {
+            "DEFAULT VALUES".to_string()
+        } else {
+            format!("({default_columns}) VALUES {default_row}")
+        };
+
+        let insert = if args.checked {
+            let query = format!(
+                "INSERT INTO {table} {default_values} RETURNING {columns}",
+                columns = args.columns().collect::<Vec<_>>().join(", ")
+            );
+
+            quote::quote! {
+                ::sqlx::query_as!(
+                    #source_ident,
+                    #query,
+                    #(#query_values),*
+                ).fetch_one(executor).await?
+            }
+        } else {
+            let query = format!("INSERT INTO {table} {default_values} RETURNING {columns}");
+
+            quote::quote! {
+                ::sqlx::query_as::<_, Self::SourceEntity>(#query).fetch_one(executor).await?
+            }
+        };
 
         return quote::quote! {
             #vis struct #ident;
@@ -73,12 +128,26 @@ pub(crate) fn generate_insert(args: &EntityCtx) -> TokenStream {
             impl ::gremlin_orm::InsertableEntity for #ident {
                 type SourceEntity = #source_ident;
 
-                async fn insert<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Self::SourceEntity, ::sqlx::Error> {
-                    ::sqlx::query_as!(
-                        #source_ident,
-                        #query,
-                        #(#query_values),*
-                    ).fetch_one(executor).await
+                const BATCH_SIZE: usize = #MAX_BIND_PARAMETERS;
+
+                async fn insert<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Self::SourceEntity, ::gremlin_orm::Error> {
+                    Ok(#insert)
+                }
+
+                async fn insert_batch<'a>(values: &[Self], executor: impl ::sqlx::PgExecutor<'a>) -> Result<Vec<Self::SourceEntity>, ::gremlin_orm::Error> {
+                    if values.is_empty() {
+                        return Ok(vec![]);
+                    }
+
+                    let query = format!(
+                        "INSERT INTO {table} ({column}) VALUES {rows} RETURNING {columns}",
+                        table = #table,
+                        column = #default_columns,
+                        rows = vec![#default_row; values.len()].join(", "),
+                        columns = #columns,
+                    );
+
+                    Ok(::sqlx::query_as::<_, Self::SourceEntity>(::sqlx::AssertSqlSafe(query)).fetch_all(executor).await?)
                 }
             }
         };
@@ -90,7 +159,7 @@ pub(crate) fn generate_insert(args: &EntityCtx) -> TokenStream {
     for field in insertable_base.iter().filter(|field| !field.default) {
         let ident = field.ident.clone();
 
-        static_field_names.push(ident.to_string());
+        static_field_names.push(field.column_sql());
 
         static_field_binds.push(quote::quote! {
             query = query.bind(&self.#ident);
@@ -100,13 +169,67 @@ pub(crate) fn generate_insert(args: &EntityCtx) -> TokenStream {
     let mut optional_field_names = vec![];
     let mut optional_field_binds = vec![];
 
+    let copy_columns = insertable_base
+        .iter()
+        .map(|field| field.column_sql())
+        .collect::<Vec<_>>();
+
+    let batch_fields = copy_columns
+        .iter()
+        .chain(&timestamp_columns)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut batch_placeholders = vec![];
+    let mut batch_binds = vec![];
+
+    for field in insertable_base.iter() {
+        let ident = field.ident.clone();
+
+        if field.default {
+            batch_placeholders.push(quote::quote! {
+                match &value.#ident {
+                    ::gremlin_orm::Defaultable::Value(_) => {
+                        placeholders += 1;
+                        row.push(format!("${placeholders}"));
+                    }
+                    ::gremlin_orm::Defaultable::Default => row.push("DEFAULT".to_string()),
+                }
+            });
+
+            batch_binds.push(quote::quote! {
+                if let ::gremlin_orm::Defaultable::Value(v) = &value.#ident {
+                    query = query.bind(v);
+                }
+            });
+        } else {
+            batch_placeholders.push(quote::quote! {
+                placeholders += 1;
+                row.push(format!("${placeholders}"));
+            });
+
+            batch_binds.push(quote::quote! {
+                query = query.bind(&value.#ident);
+            });
+        }
+    }
+
+    for _ in &timestamp_columns {
+        batch_placeholders.push(quote::quote! {
+            row.push("NOW()".to_string());
+        });
+    }
+
+    let batch_size = MAX_BIND_PARAMETERS / insertable_base.len();
+
     for field in insertable_base.iter().filter(|field| field.default) {
         let ident = field.ident.clone();
-        let ident_str = ident.to_string();
+        let column = field.column_sql();
 
         optional_field_names.push(quote::quote! {
             if let ::gremlin_orm::Defaultable::Value(_) = &self.#ident {
-                fields.push(#ident_str);
+                fields.push(#column);
             }
         });
 
@@ -117,7 +240,176 @@ pub(crate) fn generate_insert(args: &EntityCtx) -> TokenStream {
         });
     }
 
-    let empty_fields_query = format!("INSERT INTO {table} DEFAULT VALUES RETURNING {columns}");
+    // Builds the query inserting the values, with the given `ON CONFLICT` clause computed from the
+    // inserted `fields`
+    let build_query = |on_conflict: TokenStream| {
+        quote::quote! {
+            let mut fields: Vec<&str> = vec![#(#static_field_names),*];
+            #(#optional_field_names)*
+
+            let timestamps: &[&str] = &[#(#timestamp_columns),*];
+
+            let table = #table;
+            let columns = #columns;
+            let on_conflict: String = #on_conflict;
+
+            let query = if fields.is_empty() && timestamps.is_empty() {
+                format!("INSERT INTO {table} DEFAULT VALUES{on_conflict} RETURNING {columns}")
+            } else {
+                let placeholders = (1..=fields.len())
+                    .map(|i| format!("${}", i))
+                    .chain(timestamps.iter().map(|_| "NOW()".to_string()))
+                    .collect::<Vec<_>>();
+
+                format!(
+                    "INSERT INTO {table} ({fields}) VALUES ({placeholders}){on_conflict} RETURNING {columns}",
+                    fields = fields.iter().chain(timestamps).copied().collect::<Vec<_>>().join(", "),
+                    placeholders = placeholders.join(", ")
+                )
+            };
+
+            let mut query = ::sqlx::query_as::<_, Self::SourceEntity>(::sqlx::AssertSqlSafe(query));
+            #(#static_field_binds)*
+            #(#optional_field_binds)*
+        }
+    };
+
+    let insert_query = build_query(quote::quote! { String::new() });
+
+    let upsert = args.conflict_target().map(|target| {
+        let conflict_columns = target.columns().to_vec();
+        let target = target.sql();
+
+        let ignore_query = build_query(quote::quote! {
+            format!(" ON CONFLICT {} DO NOTHING", #target)
+        });
+
+        // The version is incremented like on update, so stale copies of the row are detected. The
+        // existing row has to be referred to by the table, as `EXCLUDED` has the same columns.
+        let (version_column, version_set) = match args.version() {
+            Some(field) => {
+                let column = field.column_sql();
+                let set = format!("{column} = {table}.{column} + 1");
+                (quote::quote! { Some(#column) }, quote::quote! { Some(#set) })
+            }
+            None => (quote::quote! { None }, quote::quote! { None }),
+        };
+
+        // Update all inserted columns which are not part of the conflict target. When only those
+        // are inserted, they are updated to their current value, so the row is still returned.
+        let upsert_query = build_query(quote::quote! {{
+            let conflict_columns: &[&str] = &[#(#conflict_columns),*];
+            let version: Option<&str> = #version_column;
+
+            let inserted = fields
+                .iter()
+                .filter(|field| Some(**field) != version)
+                .collect::<Vec<_>>();
+
+            let mut updated = inserted
+                .iter()
+                .copied()
+                .filter(|field| !conflict_columns.contains(field))
+                .collect::<Vec<_>>();
+
+            if updated.is_empty() {
+                updated = inserted;
+            }
+
+            // The row is touched by the update, even when no values changed
+            let touched: &[&str] = &[#(#updated_at_columns),*];
+            updated.extend(touched);
+
+            let mut updated = updated
+                .into_iter()
+                .map(|field| format!("{field} = EXCLUDED.{field}"))
+                .collect::<Vec<_>>();
+
+            let version_set: Option<&str> = #version_set;
+            updated.extend(version_set.map(str::to_string));
+
+            if updated.is_empty() {
+                format!(" ON CONFLICT {} DO NOTHING", #target)
+            } else {
+                format!(" ON CONFLICT {} DO UPDATE SET {}", #target, updated.join(", "))
+            }
+        }});
+
+        quote::quote! {
+            impl ::gremlin_orm::UpsertableEntity for #ident {
+                type SourceEntity = #source_ident;
+
+                async fn upsert<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Self::SourceEntity, ::gremlin_orm::Error> {
+                    #upsert_query
+
+                    Ok(query.fetch_one(executor).await?)
+                }
+
+                async fn insert_or_ignore<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Option<Self::SourceEntity>, ::gremlin_orm::Error> {
+                    #ignore_query
+
+                    Ok(query.fetch_optional(executor).await?)
+                }
+            }
+        }
+    });
+
+    // COPY can not use NOW(), so the timestamp columns are encoded from the fetched NOW() instead
+    let copy_statement = format!(
+        "COPY {table} ({copy_columns}) FROM STDIN (FORMAT binary)",
+        copy_columns = copy_columns
+            .iter()
+            .chain(&timestamp_columns)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    // The binary COPY tuple header stores the field count as an i16
+    let Ok(copy_field_count) = i16::try_from(insertable_base.len() + timestamp_columns.len())
+    else {
+        abort!(
+            args.ident,
+            "The entity has more columns than can be inserted with COPY (at most {})",
+            i16::MAX
+        )
+    };
+    let copy_timestamps = timestamp_columns.iter().map(|_| {
+        quote::quote! {
+            buf.encode_now();
+        }
+    });
+
+    let copy_fields = insertable_base
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone();
+            let ty = field
+                .cast
+                .clone()
+                .map(|cast| quote::quote! { #cast })
+                .unwrap_or_else(|| {
+                    let ty = &field.ty;
+                    quote::quote! { #ty }
+                });
+
+            if field.default {
+                let message = format!(
+                    "The default value of `{ident}` can not be used when copying, provide a value instead"
+                );
+
+                quote::quote! {
+                    match &self.#ident {
+                        ::gremlin_orm::Defaultable::Value(v) => buf.encode::<#ty>(v)?,
+                        ::gremlin_orm::Defaultable::Default => return Err(buf.unsupported(#message)),
+                    }
+                }
+            } else {
+                quote::quote! {
+                    buf.encode::<#ty>(&self.#ident)?;
+                }
+            }
+        })
+        .collect::<Vec<_>>();
 
     let stream = quote::quote! {
         #vis struct #ident {
@@ -127,33 +419,59 @@ pub(crate) fn generate_insert(args: &EntityCtx) -> TokenStream {
         impl ::gremlin_orm::InsertableEntity for #ident {
             type SourceEntity = #source_ident;
 
-            async fn insert<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Self::SourceEntity, ::sqlx::Error> {
-                let mut fields = vec![#(#static_field_names),*];
-                #(#optional_field_names)*
+            const BATCH_SIZE: usize = #batch_size;
 
-                let table = #table;
-                let columns = #columns;
+            async fn insert<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Self::SourceEntity, ::gremlin_orm::Error> {
+                #insert_query
 
-                if fields.is_empty() {
-                    let query = format!("INSERT INTO {table} DEFAULT VALUES RETURNING {columns}");
-                    ::sqlx::query_as::<_, Self::SourceEntity>(::sqlx::AssertSqlSafe(query)).fetch_one(executor).await
-                } else {
-                    let placeholders = (1..=fields.len())
-                        .map(|i| format!("${}", i))
-                        .collect::<Vec<_>>();
-
-                    let query = format!(
-                        "INSERT INTO {table} ({fields}) VALUES ({placeholders}) RETURNING {columns}",
-                        fields = fields.join(", "),
-                        placeholders = placeholders.join(", ")
-                    );
+                Ok(query.fetch_one(executor).await?)
+            }
 
-                    let mut query = ::sqlx::query_as::<_, Self::SourceEntity>(::sqlx::AssertSqlSafe(query));
-                    #(#static_field_binds)*
-                    #(#optional_field_binds)*
+            async fn insert_batch<'a>(values: &[Self], executor: impl ::sqlx::PgExecutor<'a>) -> Result<Vec<Self::SourceEntity>, ::gremlin_orm::Error> {
+                if values.is_empty() {
+                    return Ok(vec![]);
+                }
 
-                    query.fetch_one(executor).await
+                let mut placeholders = 0;
+
+                let rows = values
+                    .iter()
+                    .map(|value| {
+                        let mut row = vec![];
+                        #(#batch_placeholders)*
+                        format!("({})", row.join(", "))
+                    })
+                    .collect::<Vec<_>>();
+
+                let query = format!(
+                    "INSERT INTO {table} ({fields}) VALUES {rows} RETURNING {columns}",
+                    table = #table,
+                    fields = #batch_fields,
+                    rows = rows.join(", "),
+                    columns = #columns,
+                );
+
+                let mut query = ::sqlx::query_as::<_, Self::SourceEntity>(::sqlx::AssertSqlSafe(query));
+
+                for value in values {
+                    #(#batch_binds)*
                 }
+
+                Ok(query.fetch_all(executor).await?)
+            }
+        }
+
+        #upsert
+
+        impl ::gremlin_orm::CopyableEntity for #ident {
+            const COPY_STATEMENT: &'static str = #copy_statement;
+
+            fn encode_row(&self, buf: &mut ::gremlin_orm::__private::CopyBuffer) -> Result<(), ::gremlin_orm::Error> {
+                buf.start_row(#copy_field_count);
+                #(#copy_fields)*
+                #(#copy_timestamps)*
+
+                Ok(())
             }
         }
     };
@@ -0,0 +1,232 @@
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use syn::{Ident, Path, ext::IdentExt};
+
+use crate::{EntityCtx, EntityFieldCtx, ParseCtxError, relation::to_snake_case};
+
+/// The entities linked by a join table, like `join(left = Artist, right = Release)`
+#[derive(Debug, Clone, FromMeta)]
+pub(crate) struct JoinArgs {
+    left: Path,
+    right: Path,
+    left_fk: Option<Ident>,
+    right_fk: Option<Ident>,
+    left_pk: Option<Ident>,
+    right_pk: Option<Ident>,
+}
+
+/// A join table, matching the `left_fk` field to the `left_pk` field of the left entity, and the
+/// `right_fk` field to the `right_pk` field of the right entity
+#[derive(Debug, Clone)]
+pub(crate) struct JoinCtx {
+    left: Path,
+    right: Path,
+    left_fk: EntityFieldCtx,
+    right_fk: EntityFieldCtx,
+    left_pk: Ident,
+    right_pk: Ident,
+}
+
+impl JoinCtx {
+    pub(crate) fn new(
+        args: JoinArgs,
+        data: &[EntityFieldCtx],
+        soft_delete: bool,
+    ) -> Result<Self, ParseCtxError> {
+        // Links are inserted and deleted directly, which does not combine with soft deletes
+        if soft_delete {
+            return Err(ParseCtxError::InvalidJoin);
+        }
+
+        let field = |path: &Path, fk: Option<Ident>| {
+            let fk = match fk {
+                Some(fk) => fk,
+                None => {
+                    let name = path
+                        .segments
+                        .last()
+                        .map(|segment| to_snake_case(&segment.ident.unraw().to_string()))
+                        .ok_or(ParseCtxError::InvalidApplication)?;
+
+                    quote::format_ident!("{name}_id")
+                }
+            };
+
+            data.iter()
+                .find(|field| field.ident == fk)
+                .cloned()
+                .ok_or_else(|| ParseCtxError::UnknownField(fk.to_string()))
+        };
+
+        Ok(Self {
+            left_fk: field(&args.left, args.left_fk)?,
+            right_fk: field(&args.right, args.right_fk)?,
+            left: args.left,
+            right: args.right,
+            left_pk: args.left_pk.unwrap_or_else(|| quote::format_ident!("id")),
+            right_pk: args.right_pk.unwrap_or_else(|| quote::format_ident!("id")),
+        })
+    }
+}
+
+pub(crate) fn generate_join(args: &EntityCtx) -> TokenStream {
+    let Some(join) = &args.join else {
+        return TokenStream::new();
+    };
+
+    let ident = &args.ident;
+    let table = &args.table;
+    let left = &join.left;
+    let right = &join.right;
+    let left_pk = &join.left_pk;
+    let right_pk = &join.right_pk;
+    let left_fk = &join.left_fk.ident;
+    let right_fk = &join.right_fk.ident;
+    let right_ty = &join.right_fk.ty;
+
+    let left_column = join.left_fk.column_sql();
+    let right_column = join.right_fk.column_sql();
+
+    let link = format!(
+        "INSERT INTO {table} ({left_column}, {right_column}) SELECT $1, $2 WHERE NOT EXISTS (SELECT 1 FROM {table} WHERE {left_column} = $1 AND {right_column} = $2) ON CONFLICT DO NOTHING"
+    );
+    let unlink = format!("DELETE FROM {table} WHERE {left_column} = $1 AND {right_column} = $2");
+    let unlink_missing =
+        format!("DELETE FROM {table} WHERE {left_column} = $1 AND NOT ({right_column} = ANY($2))");
+    // The `= ANY` condition of the existing links allows PostgreSQL to infer the type of the
+    // array, which it can not do for `UNNEST` when the query is checked at compile time
+    let link_missing = format!(
+        "WITH existing AS (SELECT {right_column} AS value FROM {table} WHERE {left_column} = $1 AND {right_column} = ANY($2)) INSERT INTO {table} ({left_column}, {right_column}) SELECT DISTINCT $1, value FROM UNNEST($2) AS rights(value) WHERE NOT EXISTS (SELECT 1 FROM existing WHERE existing.value = rights.value) ON CONFLICT DO NOTHING"
+    );
+
+    let (link, unlink, unlink_missing, link_missing) = if args.checked {
+        (
+            quote::quote! {
+                ::sqlx::query!(#link, left.#left_pk, right.#right_pk)
+                    .execute(executor)
+                    .await?
+            },
+            quote::quote! {
+                ::sqlx::query!(#unlink, left.#left_pk, right.#right_pk)
+                    .execute(executor)
+                    .await?
+            },
+            quote::quote! {
+                ::sqlx::query!(#unlink_missing, left.#left_pk, &rights)
+                    .execute(&mut *tx)
+                    .await?
+            },
+            quote::quote! {
+                ::sqlx::query!(#link_missing, left.#left_pk, &rights)
+                    .execute(&mut *tx)
+                    .await?
+            },
+        )
+    } else {
+        (
+            quote::quote! {
+                ::sqlx::query(#link)
+                    .bind(&left.#left_pk)
+                    .bind(&right.#right_pk)
+                    .execute(executor)
+                    .await?
+            },
+            quote::quote! {
+                ::sqlx::query(#unlink)
+                    .bind(&left.#left_pk)
+                    .bind(&right.#right_pk)
+                    .execute(executor)
+                    .await?
+            },
+            quote::quote! {
+                ::sqlx::query(#unlink_missing)
+                    .bind(&left.#left_pk)
+                    .bind(&rights)
+                    .execute(&mut *tx)
+                    .await?
+            },
+            quote::quote! {
+                ::sqlx::query(#link_missing)
+                    .bind(&left.#left_pk)
+                    .bind(&rights)
+                    .execute(&mut *tx)
+                    .await?
+            },
+        )
+    };
+
+    quote::quote! {
+        impl ::gremlin_orm::JoinEntity for #ident {
+            type Left = #left;
+            type Right = #right;
+
+            async fn link<'a>(
+                left: &<Self::Left as ::gremlin_orm::PageableEntity>::Pk,
+                right: &<Self::Right as ::gremlin_orm::PageableEntity>::Pk,
+                executor: impl ::sqlx::PgExecutor<'a>,
+            ) -> Result<bool, ::gremlin_orm::Error> {
+                let result = #link;
+
+                Ok(result.rows_affected() > 0)
+            }
+
+            async fn unlink<'a>(
+                left: &<Self::Left as ::gremlin_orm::PageableEntity>::Pk,
+                right: &<Self::Right as ::gremlin_orm::PageableEntity>::Pk,
+                executor: impl ::sqlx::PgExecutor<'a>,
+            ) -> Result<bool, ::gremlin_orm::Error> {
+                let result = #unlink;
+
+                Ok(result.rows_affected() > 0)
+            }
+
+            async fn set_links<'a>(
+                left: &<Self::Left as ::gremlin_orm::PageableEntity>::Pk,
+                rights: &[<Self::Right as ::gremlin_orm::PageableEntity>::Pk],
+                executor: impl ::sqlx::Acquire<'a, Database = ::sqlx::Postgres>,
+            ) -> Result<(), ::gremlin_orm::Error> {
+                let rights = rights
+                    .iter()
+                    .map(|right| right.#right_pk.clone())
+                    .collect::<Vec<#right_ty>>();
+
+                let mut tx = executor.begin().await?;
+
+                #unlink_missing;
+                #link_missing;
+
+                tx.commit().await?;
+
+                Ok(())
+            }
+
+            async fn linked_right<'a>(
+                left: &<Self::Left as ::gremlin_orm::PageableEntity>::Pk,
+                executor: impl ::sqlx::PgExecutor<'a>,
+            ) -> Result<Vec<Self::Right>, ::gremlin_orm::Error> {
+                let columns = <Self as ::gremlin_orm::QueryableEntity>::columns();
+                let links = <Self as ::gremlin_orm::QueryableEntity>::query()
+                    .filter(columns.#left_fk.eq(left.#left_pk.clone()));
+
+                <#right as ::gremlin_orm::QueryableEntity>::query()
+                    .filter(<#right as ::gremlin_orm::QueryableEntity>::columns().#right_pk.in_query(&links, columns.#right_fk))
+                    .fetch_all(executor)
+                    .await
+            }
+
+            async fn linked_left<'a>(
+                right: &<Self::Right as ::gremlin_orm::PageableEntity>::Pk,
+                executor: impl ::sqlx::PgExecutor<'a>,
+            ) -> Result<Vec<Self::Left>, ::gremlin_orm::Error> {
+                let columns = <Self as ::gremlin_orm::QueryableEntity>::columns();
+                let links = <Self as ::gremlin_orm::QueryableEntity>::query()
+                    .filter(columns.#right_fk.eq(right.#right_pk.clone()));
+
+                <#left as ::gremlin_orm::QueryableEntity>::query()
+                    .filter(<#left as ::gremlin_orm::QueryableEntity>::columns().#left_pk.in_query(&links, columns.#left_fk))
+                    .fetch_all(executor)
+                    .await
+            }
+        }
+    }
+}
@@ -1,15 +1,28 @@
 //! # `gremlin-orm-macro`
 
-use darling::{FromDeriveInput, FromField, ast::Data, util::Ignored};
+use darling::{
+    FromDeriveInput, FromField, FromMeta,
+    ast::Data,
+    util::{Ignored, PathList},
+};
 use proc_macro::TokenStream;
 use proc_macro_error2::abort;
+use proc_macro2::Span;
 use quote::ToTokens;
-use syn::{DeriveInput, Ident, Path, parse_macro_input};
+use relation::{RelationCtx, RelationKind};
+use syn::{DeriveInput, Ident, Path, ext::IdentExt, parse_macro_input};
 use thiserror::Error;
 
 mod delete;
 mod fetch;
+mod ident;
 mod insert;
+mod join;
+mod patch;
+mod query;
+mod relation;
+mod rename;
+mod soft_delete;
 mod stream;
 mod update;
 
@@ -31,23 +44,34 @@ fn generate(args: DeriveInput) -> Result<TokenStream, GeneratorError> {
 
     let args = match EntityCtx::try_from(args) {
         Ok(v) => v,
-        Err(ParseCtxError::InvalidApplication) => {
-            abort!(ident, ParseCtxError::InvalidApplication)
+        Err(ParseCtxError::InvalidFieldRelation(span)) => {
+            abort!(span, ParseCtxError::InvalidFieldRelation(span))
         }
+        Err(err) => abort!(ident, err),
     };
 
     let insert_stream = insert::generate_insert(&args);
     let update_stream = update::generate_update(&args);
+    let patch_stream = patch::generate_patch(&args);
     let stream_stream = stream::generate_stream(&args);
     let delete_stream = delete::generate_delete(&args);
+    let soft_delete_stream = delete::generate_soft_delete(&args);
     let get_by_id_stream = fetch::generate_fetch(&args);
+    let query_stream = query::generate_query(&args);
+    let relation_stream = relation::generate_relations(&args);
+    let join_stream = join::generate_join(&args);
 
     let stream = quote::quote! {
         #insert_stream
         #update_stream
+        #patch_stream
         #stream_stream
         #delete_stream
+        #soft_delete_stream
         #get_by_id_stream
+        #query_stream
+        #relation_stream
+        #join_stream
     };
 
     Ok(stream.into())
@@ -59,7 +83,11 @@ struct EntityCtx {
     vis: syn::Visibility,
     data: Vec<EntityFieldCtx>,
     table: String,
-    soft_delete: Option<String>,
+    soft_delete: Option<soft_delete::SoftDeleteCtx>,
+    checked: bool,
+    conflict: Option<ConflictTarget>,
+    relations: Vec<relation::RelationCtx>,
+    join: Option<join::JoinCtx>,
 }
 
 impl EntityCtx {
@@ -67,16 +95,96 @@ impl EntityCtx {
         self.data.iter().filter(|field| field.pk)
     }
 
+    /// The fields which are used to update the entity: the primary key(s), the version and all
+    /// fields which can be changed
+    fn updatable(&self) -> impl Iterator<Item = &EntityFieldCtx> {
+        self.data.iter().filter(|field| {
+            (field.pk || field.version || !field.generated)
+                && !field.is_timestamp()
+                && self
+                    .soft_delete
+                    .as_ref()
+                    .is_none_or(|soft_delete| field.column != soft_delete.column)
+        })
+    }
+
+    /// The field used for optimistic locking, if any
+    fn version(&self) -> Option<&EntityFieldCtx> {
+        self.data.iter().find(|field| field.version)
+    }
+
+    /// The assignments made by every update, regardless of the changed fields: touching the
+    /// `updated_at` timestamps and incrementing the version, as pairs of columns and values
+    fn update_assignments(&self) -> Vec<(String, String)> {
+        let mut set = self
+            .data
+            .iter()
+            .filter(|field| field.updated_at && !field.generated)
+            .map(|field| (field.column_sql(), "NOW()".to_string()))
+            .collect::<Vec<_>>();
+
+        if let Some(version) = self.version() {
+            let column = version.column_sql();
+            set.push((column.clone(), format!("{column} + 1")));
+        }
+
+        set
+    }
+
+    /// The assignments made by every update, like [`EntityCtx::update_assignments`]
+    fn update_set(&self) -> Vec<String> {
+        self.update_assignments()
+            .into_iter()
+            .map(|(column, value)| format!("{column} = {value}"))
+            .collect()
+    }
+
+    /// The conflict target used when upserting, defaulting to the primary keys
+    fn conflict_target(&self) -> Option<ConflictTarget> {
+        self.conflict.clone().or_else(|| {
+            let pks = self
+                .pks()
+                .map(|field| field.column_sql())
+                .collect::<Vec<_>>();
+
+            (!pks.is_empty()).then_some(ConflictTarget::Columns(pks))
+        })
+    }
+
     fn columns(&self) -> impl Iterator<Item = String> {
-        self.data.iter().cloned().map(|field| {
-            if let Some(cast) = field.cast {
+        self.data.iter().map(|field| {
+            if let Some(cast) = &field.cast {
                 format!(
-                    r#"{ident} AS "{ident}!: {cast}""#,
-                    ident = field.ident,
+                    r#"{column} AS "{ident}!: {cast}""#,
+                    column = field.column_sql(),
+                    ident = field.ident.unraw(),
                     cast = cast.to_token_stream()
                 )
+            } else if field.is_renamed() {
+                format!(
+                    r#"{column} AS "{ident}""#,
+                    column = field.column_sql(),
+                    ident = field.ident.unraw()
+                )
             } else {
-                field.ident.to_string()
+                field.column_sql()
+            }
+        })
+    }
+
+    /// Columns to select when the query is executed at runtime, and the result is mapped using
+    /// `sqlx::FromRow`. This omits the type overrides, as those are only understood by the sqlx
+    /// macros.
+    fn runtime_columns(&self) -> impl Iterator<Item = String> {
+        self.data.iter().map(|field| {
+            if field.is_renamed() {
+                format!(
+                    r#"{column} AS "{ident}""#,
+                    column = field.column_sql(),
+                    ident = field.ident.unraw()
+                )
+            } else {
+                field.column_sql()
             }
         })
     }
@@ -87,21 +195,105 @@ impl TryFrom<EntityArgs> for EntityCtx {
 
     fn try_from(value: EntityArgs) -> Result<Self, Self::Error> {
         let mut data = vec![];
+        let mut field_relations = vec![];
+        let quote_identifiers = value.quote_identifiers.unwrap_or(true);
 
         for row in value
             .data
             .take_struct()
             .ok_or(ParseCtxError::InvalidApplication)?
         {
-            data.push(row.try_into()?);
+            let mut field: EntityFieldCtx = row.clone().try_into()?;
+
+            field.quote_identifiers = quote_identifiers;
+            field.column = match (row.column, value.rename_all) {
+                (Some(column), _) => column.0,
+                (None, Some(rule)) => rule.apply(&field.ident.unraw().to_string()),
+                (None, None) => field.ident.unraw().to_string(),
+            };
+
+            if let Some(relation) = row.belongs_to {
+                // The annotated field is the foreign key
+                if let Some(span) = relation.fk_span() {
+                    return Err(ParseCtxError::InvalidFieldRelation(span));
+                }
+
+                field_relations.push(relation.with_fk(field.ident.clone()));
+            }
+
+            data.push(field);
+        }
+
+        if data.iter().filter(|field| field.version).count() > 1
+            || data.iter().any(|field| field.version && field.pk)
+        {
+            return Err(ParseCtxError::InvalidVersion);
         }
 
+        let relations = value
+            .belongs_to
+            .into_iter()
+            .chain(field_relations)
+            .map(|args| (RelationKind::BelongsTo, args))
+            .chain(
+                value
+                    .has_many
+                    .into_iter()
+                    .map(|args| (RelationKind::HasMany, args)),
+            )
+            .map(|(kind, args)| RelationCtx::new(kind, args, &value.ident, &data))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let join = value
+            .join
+            .map(|args| join::JoinCtx::new(args, &data, value.soft_delete.is_some()))
+            .transpose()?;
+
+        let conflict = match value.conflict {
+            Some(ConflictArgs {
+                fields,
+                constraint: None,
+            }) if !fields.is_empty() => {
+                let mut columns = vec![];
+
+                for path in fields.iter() {
+                    let field = data
+                        .iter()
+                        .find(|field: &&EntityFieldCtx| path.is_ident(&field.ident))
+                        .ok_or_else(|| {
+                            ParseCtxError::UnknownField(path.to_token_stream().to_string())
+                        })?;
+
+                    columns.push(field.column_sql());
+                }
+
+                Some(ConflictTarget::Columns(columns))
+            }
+            Some(ConflictArgs {
+                fields,
+                constraint: Some(constraint),
+            }) if fields.is_empty() => Some(ConflictTarget::Constraint(ident::quote(
+                &constraint.0,
+                quote_identifiers,
+            ))),
+            Some(_) => return Err(ParseCtxError::InvalidConflictTarget),
+            None => None,
+        };
+
         Ok(Self {
             ident: value.ident,
             vis: value.vis,
             data,
-            table: value.table,
-            soft_delete: value.soft_delete,
+            table: value.table.sql(quote_identifiers),
+            soft_delete: value
+                .soft_delete
+                .map(|args| soft_delete::SoftDeleteCtx::new(args, quote_identifiers)),
+            checked: value
+                .checked
+                .unwrap_or(cfg!(not(feature = "runtime-checked"))),
+            conflict,
+            relations,
+            join,
         })
     }
 }
@@ -109,10 +301,15 @@ impl TryFrom<EntityArgs> for EntityCtx {
 #[derive(Debug, Clone)]
 struct EntityFieldCtx {
     ident: Ident,
+    column: String,
+    quote_identifiers: bool,
     vis: syn::Visibility,
     ty: syn::Type,
     pk: bool,
     generated: bool,
+    version: bool,
+    created_at: bool,
+    updated_at: bool,
     deref: bool,
     as_ref: bool,
     default: bool,
@@ -120,6 +317,48 @@ struct EntityFieldCtx {
 }
 
 impl EntityFieldCtx {
+    /// The column name, quoted for usage in a query
+    pub(crate) fn column_sql(&self) -> String {
+        ident::quote(&self.column, self.quote_identifiers)
+    }
+
+    /// Whether the column is set to the current timestamp by the generated queries
+    pub(crate) fn is_timestamp(&self) -> bool {
+        self.created_at || self.updated_at
+    }
+
+    /// Whether the column identifies the row when updating it. The version is matched like the
+    /// primary key(s), so stale values do not update the row.
+    pub(crate) fn is_key(&self) -> bool {
+        self.pk || self.version
+    }
+
+    /// Whether the column name differs from the field name
+    pub(crate) fn is_renamed(&self) -> bool {
+        self.ident.unraw() != self.column
+    }
+
+    /// The value of the field on `self`, to bind to a query which is checked at runtime
+    pub(crate) fn runtime_value(&self) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+
+        if self.as_ref {
+            quote::quote! { self.#ident.as_ref() }
+        } else if self.deref {
+            quote::quote! { self.#ident.as_deref() }
+        } else {
+            quote::quote! { &self.#ident }
+        }
+    }
+
+    /// The type the column is decoded as, which is the type override if present
+    pub(crate) fn decode_ty(&self) -> proc_macro2::TokenStream {
+        match &self.cast {
+            Some(cast) => cast.to_token_stream(),
+            None => self.ty.to_token_stream(),
+        }
+    }
+
     pub(crate) fn cast(&self) -> proc_macro2::TokenStream {
         self.cast
             .clone()
@@ -132,22 +371,71 @@ impl EntityFieldCtx {
     }
 }
 
+/// The target of an `ON CONFLICT` clause
+#[derive(Debug, Clone)]
+enum ConflictTarget {
+    Columns(Vec<String>),
+    Constraint(String),
+}
+
+impl ConflictTarget {
+    /// The columns in the conflict target, empty when targeting a constraint
+    fn columns(&self) -> &[String] {
+        match self {
+            Self::Columns(columns) => columns,
+            Self::Constraint(_) => &[],
+        }
+    }
+
+    fn sql(&self) -> String {
+        match self {
+            Self::Columns(columns) => format!("({})", columns.join(", ")),
+            Self::Constraint(constraint) => format!("ON CONSTRAINT {constraint}"),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 enum ParseCtxError {
     #[error("The `Entity` macro can only be applied to a struct with named fields")]
     InvalidApplication,
+    #[error("The field `{0}` does not exist on the entity")]
+    UnknownField(String),
+    #[error("The conflict target should specify either `fields` or `constraint`")]
+    InvalidConflictTarget,
+    #[error("The relation `{0}` should have as many `fk` as `references` fields")]
+    InvalidRelation(String),
+    #[error(
+        "The `fk` option is not allowed on a field-level `belongs_to`, as the annotated field is the foreign key"
+    )]
+    InvalidFieldRelation(Span),
+    #[error(
+        "The `has_many` relation to `{0}` should specify a `name`, as it can not be pluralized"
+    )]
+    MissingRelationName(String),
+    #[error("A join entity can not be soft deleted")]
+    InvalidJoin,
+    #[error("Only a single field, which is not a primary key, can be used as `version`")]
+    InvalidVersion,
 }
 
 impl TryFrom<EntityField> for EntityFieldCtx {
     type Error = ParseCtxError;
 
     fn try_from(value: EntityField) -> Result<Self, Self::Error> {
+        let ident = value.ident.ok_or(ParseCtxError::InvalidApplication)?;
+
         Ok(Self {
-            ident: value.ident.ok_or(ParseCtxError::InvalidApplication)?,
+            column: ident.unraw().to_string(),
+            quote_identifiers: true,
+            ident,
             vis: value.vis,
             ty: value.ty,
             pk: value.pk,
             generated: value.generated,
+            version: value.version,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
             deref: value.deref,
             as_ref: value.as_ref,
             default: value.default,
@@ -162,8 +450,24 @@ struct EntityArgs {
     ident: Ident,
     vis: syn::Visibility,
     data: Data<Ignored, EntityField>,
-    table: String,
-    soft_delete: Option<String>,
+    table: ident::TableName,
+    soft_delete: Option<soft_delete::SoftDeleteArgs>,
+    rename_all: Option<rename::RenameRule>,
+    quote_identifiers: Option<bool>,
+    checked: Option<bool>,
+    conflict: Option<ConflictArgs>,
+    #[darling(multiple)]
+    belongs_to: Vec<relation::RelationArgs>,
+    #[darling(multiple)]
+    has_many: Vec<relation::RelationArgs>,
+    join: Option<join::JoinArgs>,
+}
+
+#[derive(Debug, FromMeta)]
+struct ConflictArgs {
+    #[darling(default)]
+    fields: PathList,
+    constraint: Option<ident::Identifier>,
 }
 
 #[derive(Debug, Clone, FromField)]
@@ -177,12 +481,20 @@ struct EntityField {
     #[darling(default)]
     generated: bool,
     #[darling(default)]
+    version: bool,
+    #[darling(default)]
+    created_at: bool,
+    #[darling(default)]
+    updated_at: bool,
+    #[darling(default)]
     default: bool,
     #[darling(default)]
     deref: bool,
     #[darling(default)]
     as_ref: bool,
     cast: Option<syn::Path>,
+    column: Option<ident::Identifier>,
+    belongs_to: Option<relation::RelationArgs>,
 }
 
 #[derive(Debug, Error)]
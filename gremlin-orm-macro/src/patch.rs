@@ -0,0 +1,214 @@
+use proc_macro2::TokenStream;
+
+use crate::{EntityCtx, relation::option_inner};
+
+pub(crate) fn generate_patch(args: &EntityCtx) -> TokenStream {
+    let base = args.updatable().cloned().collect::<Vec<_>>();
+
+    let keys = base
+        .iter()
+        .filter(|field| field.is_key())
+        .collect::<Vec<_>>();
+    let fields = base
+        .iter()
+        .filter(|field| !field.is_key())
+        .collect::<Vec<_>>();
+
+    // Without primary keys the row can not be found, and without fields there is nothing to patch
+    if !keys.iter().any(|field| field.pk) || fields.is_empty() {
+        return TokenStream::default();
+    }
+
+    let vis = args.vis.clone();
+
+    let source_ident = args.ident.clone();
+    let ident = quote::format_ident!("Patch{}", args.ident);
+
+    let table = args.table.clone();
+    let columns = args.runtime_columns().collect::<Vec<_>>().join(", ");
+
+    let mut query_where = keys
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let column = field.column_sql();
+            let idx = idx + 1;
+            format!("{column} = ${idx}")
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(soft_delete) = &args.soft_delete {
+        query_where.push(soft_delete.filter_sql());
+    }
+
+    let query_where = query_where.join(" AND ");
+    let key_count = keys.len();
+
+    // Columns which are changed by every non-empty patch, regardless of the present fields
+    let static_set = args.update_set();
+
+    let key_fields = keys
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let vis = &field.vis;
+            let ty = &field.ty;
+
+            quote::quote! {
+                #vis #ident: #ty
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let patch_fields = fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let vis = &field.vis;
+
+            match option_inner(&field.ty) {
+                Some(ty) => quote::quote! {
+                    #vis #ident: ::gremlin_orm::Nullable<#ty>
+                },
+                None => {
+                    let ty = &field.ty;
+
+                    quote::quote! {
+                        #vis #ident: Option<#ty>
+                    }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let set_fields = fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let column = field.column_sql();
+
+            if option_inner(&field.ty).is_some() {
+                quote::quote! {
+                    match &self.#ident {
+                        ::gremlin_orm::Nullable::Unchanged => {}
+                        ::gremlin_orm::Nullable::Null => set.push(format!("{} = NULL", #column)),
+                        ::gremlin_orm::Nullable::Value(_) => {
+                            placeholders += 1;
+                            set.push(format!("{} = ${placeholders}", #column));
+                        }
+                    }
+                }
+            } else {
+                quote::quote! {
+                    if self.#ident.is_some() {
+                        placeholders += 1;
+                        set.push(format!("{} = ${placeholders}", #column));
+                    }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let key_binds = keys
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+
+            quote::quote! {
+                query = query.bind(&self.#ident);
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let field_binds = fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+
+            if option_inner(&field.ty).is_some() {
+                quote::quote! {
+                    if let ::gremlin_orm::Nullable::Value(value) = &self.#ident {
+                        query = query.bind(value);
+                    }
+                }
+            } else {
+                quote::quote! {
+                    if let Some(value) = &self.#ident {
+                        query = query.bind(value);
+                    }
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // The "Pk" struct does not contain the version, which is required to patch the entity
+    let from_pk = if args.version().is_none() {
+        let pk_ident = quote::format_ident!("{}Pk", args.ident);
+        let key_idents = keys.iter().map(|field| &field.ident);
+        let field_idents = fields.iter().map(|field| &field.ident);
+
+        quote::quote! {
+            impl From<#pk_ident> for #ident {
+                fn from(value: #pk_ident) -> Self {
+                    Self {
+                        #(#key_idents: value.#key_idents,)*
+                        #(#field_idents: Default::default(),)*
+                    }
+                }
+            }
+        }
+    } else {
+        TokenStream::default()
+    };
+
+    let result = if args.version().is_some() {
+        quote::quote! {
+            query.fetch_optional(executor).await?.ok_or(::gremlin_orm::Error::StaleEntity)
+        }
+    } else {
+        quote::quote! {
+            Ok(query.fetch_one(executor).await?)
+        }
+    };
+
+    quote::quote! {
+        #vis struct #ident {
+            #(#key_fields,)*
+            #(#patch_fields),*
+        }
+
+        #from_pk
+
+        impl ::gremlin_orm::PatchableEntity for #ident {
+            type SourceEntity = #source_ident;
+
+            async fn patch<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Self::SourceEntity, ::gremlin_orm::Error> {
+                let mut placeholders = #key_count;
+                let mut set: Vec<String> = vec![];
+
+                #(#set_fields)*
+
+                // Without any changes, the entity is returned as-is
+                let query = if set.is_empty() {
+                    format!("SELECT {} FROM {} WHERE {}", #columns, #table, #query_where)
+                } else {
+                    set.extend([#(#static_set.to_string()),*]);
+
+                    format!(
+                        "UPDATE {} SET {} WHERE {} RETURNING {}",
+                        #table,
+                        set.join(", "),
+                        #query_where,
+                        #columns
+                    )
+                };
+
+                let mut query = ::sqlx::query_as::<_, Self::SourceEntity>(::sqlx::AssertSqlSafe(query));
+                #(#key_binds)*
+                #(#field_binds)*
+
+                #result
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use proc_macro2::TokenStream;
+
+use crate::EntityCtx;
+
+pub(crate) fn generate_query(args: &EntityCtx) -> TokenStream {
+    let vis = args.vis.clone();
+    let source_ident = args.ident.clone();
+    let ident = quote::format_ident!("{}Columns", args.ident);
+
+    let table = args.table.clone();
+    let columns = args.columns().collect::<Vec<_>>().join(", ");
+
+    let soft_delete = match &args.soft_delete {
+        Some(soft_delete) => {
+            let predicate = soft_delete.filter_sql();
+            quote::quote! { Some(#predicate) }
+        }
+        None => quote::quote! { None },
+    };
+
+    let soft_delete_set = match &args.soft_delete {
+        Some(soft_delete) => {
+            let set = soft_delete.delete_sql();
+            quote::quote! { Some(#set) }
+        }
+        None => quote::quote! { None },
+    };
+
+    let on_update = args
+        .update_assignments()
+        .into_iter()
+        .map(|(column, value)| quote::quote! { (#column, #value) })
+        .collect::<Vec<_>>();
+
+    let column_fields = args
+        .data
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let vis = &field.vis;
+            let ty = field.decode_ty();
+
+            quote::quote! {
+                #vis #ident: ::gremlin_orm::Column<#source_ident, #ty>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let column_values = args
+        .data
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let column = field.column_sql();
+
+            quote::quote! {
+                #ident: ::gremlin_orm::Column::new(#column)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let decode_fields = args
+        .data
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let ident = &field.ident;
+            let ty = field.decode_ty();
+
+            // Type overrides are converted into the type of the field, like the sqlx macros do
+            if field.cast.is_some() {
+                quote::quote! {
+                    #ident: ::core::convert::Into::into(::sqlx::Row::try_get::<#ty, _>(row, #idx)?)
+                }
+            } else {
+                quote::quote! {
+                    #ident: ::sqlx::Row::try_get::<#ty, _>(row, #idx)?
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let pks = args.pks().collect::<Vec<_>>();
+
+    let pageable = if pks.is_empty() {
+        quote::quote! {}
+    } else {
+        let pk_ident = quote::format_ident!("{}Pk", args.ident);
+        let pk_idents = pks.iter().map(|field| &field.ident).collect::<Vec<_>>();
+
+        quote::quote! {
+            impl ::gremlin_orm::PageableEntity for #source_ident {
+                type Pk = #pk_ident;
+
+                fn after(cursor: &Self::Pk) -> ::gremlin_orm::Filter<Self> {
+                    let columns = <Self as ::gremlin_orm::QueryableEntity>::columns();
+
+                    ::gremlin_orm::RowValue::new()
+                        #(.push(columns.#pk_idents, cursor.#pk_idents.clone()))*
+                        .gt()
+                }
+
+                fn key_order() -> Vec<::gremlin_orm::Order<Self>> {
+                    let columns = <Self as ::gremlin_orm::QueryableEntity>::columns();
+
+                    vec![#(columns.#pk_idents.asc()),*]
+                }
+
+                fn keys_filter(pks: &[Self::Pk]) -> ::gremlin_orm::Filter<Self> {
+                    let columns = <Self as ::gremlin_orm::QueryableEntity>::columns();
+
+                    ::gremlin_orm::RowValues::new()
+                        #(.push(columns.#pk_idents, pks.iter().map(|pk| pk.#pk_idents.clone())))*
+                        .is_in()
+                }
+            }
+
+            impl From<&#pk_ident> for ::gremlin_orm::Filter<#source_ident> {
+                fn from(value: &#pk_ident) -> Self {
+                    let columns = <#source_ident as ::gremlin_orm::QueryableEntity>::columns();
+
+                    ::gremlin_orm::RowValue::new()
+                        #(.push(columns.#pk_idents, value.#pk_idents.clone()))*
+                        .eq()
+                }
+            }
+
+            impl From<#pk_ident> for ::gremlin_orm::Filter<#source_ident> {
+                fn from(value: #pk_ident) -> Self {
+                    Self::from(&value)
+                }
+            }
+        }
+    };
+
+    let stream = quote::quote! {
+        #vis struct #ident {
+            #(#column_fields),*
+        }
+
+        impl ::gremlin_orm::QueryableEntity for #source_ident {
+            type Columns = #ident;
+
+            const TABLE: &'static str = #table;
+            const COLUMNS: &'static str = #columns;
+            const SOFT_DELETE: Option<&'static str> = #soft_delete;
+            const SOFT_DELETE_SET: Option<&'static str> = #soft_delete_set;
+            const ON_UPDATE: &'static [(&'static str, &'static str)] = &[#(#on_update),*];
+
+            fn decode_row(row: &::sqlx::postgres::PgRow) -> Result<Self, ::sqlx::Error> {
+                Ok(Self {
+                    #(#decode_fields),*
+                })
+            }
+
+            fn columns() -> Self::Columns {
+                #ident {
+                    #(#column_values),*
+                }
+            }
+        }
+
+        #pageable
+    };
+
+    stream
+}
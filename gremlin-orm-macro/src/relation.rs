@@ -0,0 +1,403 @@
+use darling::{FromMeta, ast::NestedMeta};
+use proc_macro2::{Span, TokenStream};
+use quote::ToTokens;
+use syn::{Ident, Path, ext::IdentExt, spanned::Spanned};
+
+use crate::{EntityCtx, EntityFieldCtx, ParseCtxError};
+
+/// Whether the entity refers to the related entity, or the other way around
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RelationKind {
+    BelongsTo,
+    HasMany,
+}
+
+/// A relation as written in the attribute, like `belongs_to(Artist, fk = "artist_id")`
+#[derive(Debug, Clone)]
+pub(crate) struct RelationArgs {
+    target: Path,
+    fk: Option<FieldList>,
+    fk_span: Option<Span>,
+    references: Option<FieldList>,
+    name: Option<Ident>,
+}
+
+impl RelationArgs {
+    /// The span of the `fk` option, if the foreign key fields were set explicitly
+    pub(crate) fn fk_span(&self) -> Option<Span> {
+        self.fk_span
+    }
+
+    /// Use the given field as foreign key, for relations annotated on a field
+    pub(crate) fn with_fk(mut self, field: Ident) -> Self {
+        self.fk = Some(FieldList(vec![field]));
+        self
+    }
+}
+
+#[derive(Debug, FromMeta)]
+struct RelationOptions {
+    fk: Option<FieldList>,
+    references: Option<FieldList>,
+    name: Option<Ident>,
+}
+
+impl FromMeta for RelationArgs {
+    fn from_expr(expr: &syn::Expr) -> darling::Result<Self> {
+        match expr {
+            syn::Expr::Path(path) => Ok(Self {
+                target: path.path.clone(),
+                fk: None,
+                fk_span: None,
+                references: None,
+                name: None,
+            }),
+            _ => Err(darling::Error::unexpected_expr_type(expr)),
+        }
+    }
+
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        let Some((first, rest)) = items.split_first() else {
+            return Err(darling::Error::too_few_items(1));
+        };
+
+        let NestedMeta::Meta(syn::Meta::Path(target)) = first else {
+            return Err(darling::Error::custom("Expected the related entity").with_span(first));
+        };
+
+        let options = RelationOptions::from_list(rest)?;
+
+        let fk_span = rest.iter().find_map(|item| match item {
+            NestedMeta::Meta(meta) if meta.path().is_ident("fk") => Some(meta.span()),
+            _ => None,
+        });
+
+        Ok(Self {
+            target: target.clone(),
+            fk: options.fk,
+            fk_span,
+            references: options.references,
+            name: options.name,
+        })
+    }
+}
+
+/// A comma separated list of field names, like `"artist_id, release_id"`
+#[derive(Debug, Clone)]
+struct FieldList(Vec<Ident>);
+
+impl FromMeta for FieldList {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        value
+            .split(',')
+            .map(|field| syn::parse_str::<Ident>(field.trim()).map_err(darling::Error::custom))
+            .collect::<darling::Result<Vec<_>>>()
+            .map(Self)
+    }
+}
+
+/// A relation to another entity, matching the `local` fields of the entity to the `remote`
+/// fields of the related entity
+#[derive(Debug, Clone)]
+pub(crate) struct RelationCtx {
+    kind: RelationKind,
+    target: Path,
+    name: Ident,
+    local: Vec<EntityFieldCtx>,
+    remote: Vec<Ident>,
+}
+
+impl RelationCtx {
+    pub(crate) fn new(
+        kind: RelationKind,
+        args: RelationArgs,
+        entity: &Ident,
+        data: &[EntityFieldCtx],
+    ) -> Result<Self, ParseCtxError> {
+        let target_name = args
+            .target
+            .segments
+            .last()
+            .map(|segment| to_snake_case(&segment.ident.unraw().to_string()))
+            .ok_or(ParseCtxError::InvalidApplication)?;
+
+        let entity_name = to_snake_case(&entity.unraw().to_string());
+
+        // Appending an `s` does not pluralize these names, like `categorys` or `addresss`
+        if kind == RelationKind::HasMany
+            && args.name.is_none()
+            && (target_name.ends_with('s') || target_name.ends_with('y'))
+        {
+            return Err(ParseCtxError::MissingRelationName(target_name));
+        }
+
+        let (local, remote, name) = match kind {
+            RelationKind::BelongsTo => (
+                args.fk
+                    .map(|fk| fk.0)
+                    .unwrap_or_else(|| vec![quote::format_ident!("{target_name}_id")]),
+                args.references
+                    .map(|references| references.0)
+                    .unwrap_or_else(|| vec![quote::format_ident!("id")]),
+                target_name,
+            ),
+            RelationKind::HasMany => (
+                args.references
+                    .map(|references| references.0)
+                    .unwrap_or_else(|| {
+                        data.iter()
+                            .filter(|field| field.pk)
+                            .map(|field| field.ident.clone())
+                            .collect()
+                    }),
+                args.fk
+                    .map(|fk| fk.0)
+                    .unwrap_or_else(|| vec![quote::format_ident!("{entity_name}_id")]),
+                format!("{target_name}s"),
+            ),
+        };
+
+        let local = local
+            .iter()
+            .map(|ident| {
+                data.iter()
+                    .find(|field| field.ident == *ident)
+                    .cloned()
+                    .ok_or_else(|| ParseCtxError::UnknownField(ident.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if local.is_empty() || local.len() != remote.len() {
+            return Err(ParseCtxError::InvalidRelation(
+                args.target
+                    .segments
+                    .last()
+                    .map(|segment| segment.ident.to_string())
+                    .unwrap_or_default(),
+            ));
+        }
+
+        Ok(Self {
+            kind,
+            name: args.name.unwrap_or_else(|| quote::format_ident!("{name}")),
+            target: args.target,
+            local,
+            remote,
+        })
+    }
+}
+
+/// Convert the name of a type to snake case, like `ArtistRelease` to `artist_release`
+pub(crate) fn to_snake_case(value: &str) -> String {
+    let mut result = String::new();
+
+    for (idx, c) in value.chars().enumerate() {
+        if c.is_uppercase() {
+            if idx > 0 {
+                result.push('_');
+            }
+
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+/// The type wrapped in an `Option`, if the type is an `Option`
+pub(crate) fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    match args.args.first()? {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+pub(crate) fn generate_relations(args: &EntityCtx) -> TokenStream {
+    if args.relations.is_empty() {
+        return TokenStream::new();
+    }
+
+    let ident = &args.ident;
+    let vis = &args.vis;
+
+    let loaders = args
+        .relations
+        .iter()
+        .map(|relation| {
+            let name = &relation.name;
+            let target = &relation.target;
+
+            let (output, empty, fetch) = match relation.kind {
+                RelationKind::BelongsTo => (
+                    quote::quote! { Option<#target> },
+                    quote::quote! { None },
+                    quote::quote! { fetch_optional },
+                ),
+                RelationKind::HasMany => (
+                    quote::quote! { Vec<#target> },
+                    quote::quote! { vec![] },
+                    quote::quote! { fetch_all },
+                ),
+            };
+
+            // Unset optional keys can not refer to any entity
+            let values = relation
+                .local
+                .iter()
+                .enumerate()
+                .map(|(idx, field)| {
+                    let field_ident = &field.ident;
+                    let value = quote::format_ident!("value_{idx}");
+
+                    if option_inner(&field.ty).is_some() {
+                        quote::quote! {
+                            let Some(#value) = self.#field_ident.clone() else {
+                                return Ok(#empty);
+                            };
+                        }
+                    } else {
+                        quote::quote! {
+                            let #value = self.#field_ident.clone();
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let filters = relation
+                .remote
+                .iter()
+                .enumerate()
+                .map(|(idx, remote)| {
+                    let value = quote::format_ident!("value_{idx}");
+
+                    quote::quote! {
+                        .filter(columns.#remote.eq(#value))
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            quote::quote! {
+                #vis async fn #name<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<#output, ::gremlin_orm::Error> {
+                    #(#values)*
+
+                    let columns = <#target as ::gremlin_orm::QueryableEntity>::columns();
+
+                    <#target as ::gremlin_orm::QueryableEntity>::query()
+                        #(#filters)*
+                        .#fetch(executor)
+                        .await
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // The trait can only be implemented once per related entity, so entities with multiple
+    // relations to the same entity can not load it in bulk, instead of silently using one of them
+    let targets = args
+        .relations
+        .iter()
+        .map(|relation| relation.target.to_token_stream().to_string())
+        .collect::<Vec<_>>();
+
+    let related = args
+        .relations
+        .iter()
+        .zip(&targets)
+        .filter(|(_, target)| targets.iter().filter(|other| other == target).count() == 1)
+        .map(|(relation, _)| generate_related(ident, relation))
+        .collect::<Vec<_>>();
+
+    quote::quote! {
+        impl #ident {
+            #(#loaders)*
+        }
+
+        #(#related)*
+    }
+}
+
+fn generate_related(ident: &Ident, relation: &RelationCtx) -> TokenStream {
+    let target = &relation.target;
+    let remote = &relation.remote;
+
+    let key_types = relation
+        .local
+        .iter()
+        .map(|field| option_inner(&field.ty).unwrap_or(&field.ty))
+        .collect::<Vec<_>>();
+
+    let key_values = relation
+        .local
+        .iter()
+        .map(|field| {
+            let field_ident = &field.ident;
+
+            if option_inner(&field.ty).is_some() {
+                quote::quote! { self.#field_ident.clone()? }
+            } else {
+                quote::quote! { self.#field_ident.clone() }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let keys_filter = if let [remote] = remote.as_slice() {
+        quote::quote! {
+            columns.#remote.is_in(keys.into_iter().map(|(key,)| key))
+        }
+    } else {
+        let idx = (0..remote.len()).map(syn::Index::from);
+
+        quote::quote! {
+            ::gremlin_orm::RowValues::new()
+                #(.push(columns.#remote, keys.iter().map(|key| key.#idx.clone())))*
+                .is_in()
+        }
+    };
+
+    let key_count = remote.len();
+    let key_idx = 0..remote.len();
+
+    quote::quote! {
+        impl ::gremlin_orm::Related<#target> for #ident {
+            type Key = (#(#key_types,)*);
+
+            fn key(&self) -> Option<Self::Key> {
+                Some((#(#key_values,)*))
+            }
+
+            fn key_columns() -> Vec<&'static str> {
+                let columns = <#target as ::gremlin_orm::QueryableEntity>::columns();
+
+                vec![#(columns.#remote.name()),*]
+            }
+
+            fn keys_filter(keys: Vec<Self::Key>) -> ::gremlin_orm::Filter<#target> {
+                let columns = <#target as ::gremlin_orm::QueryableEntity>::columns();
+
+                #keys_filter
+            }
+
+            fn decode_key(row: &::sqlx::postgres::PgRow) -> Result<Self::Key, ::sqlx::Error> {
+                let offset = ::sqlx::Row::len(row) - #key_count;
+
+                Ok((#(::sqlx::Row::try_get::<#key_types, _>(row, offset + #key_idx)?,)*))
+            }
+        }
+    }
+}
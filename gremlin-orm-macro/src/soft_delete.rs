@@ -0,0 +1,151 @@
+use darling::{FromMeta, ast::NestedMeta};
+
+use crate::ident::{self, Identifier};
+
+/// How rows are marked as soft deleted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SoftDeleteKind {
+    /// A nullable timestamp, which is set to the time of deletion
+    Timestamp,
+    /// A boolean flag, which is set to `TRUE` for deleted rows
+    Bool,
+    /// Custom expressions, assigning the `set` value on delete and the `restore` value on restore,
+    /// while `filter` matches the rows which are not deleted
+    Custom {
+        set: String,
+        filter: String,
+        restore: String,
+    },
+}
+
+impl FromMeta for SoftDeleteKind {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "timestamp" => Ok(Self::Timestamp),
+            "bool" => Ok(Self::Bool),
+            _ => Err(darling::Error::unknown_value(value)),
+        }
+    }
+}
+
+/// The soft delete annotation, like `soft_delete = "deleted_at"` or
+/// `soft_delete(column = "is_deleted", kind = "bool")`
+#[derive(Debug, Clone)]
+pub(crate) struct SoftDeleteArgs {
+    column: Identifier,
+    kind: SoftDeleteKind,
+}
+
+#[derive(Debug, FromMeta)]
+struct SoftDeleteOptions {
+    column: Identifier,
+    kind: Option<SoftDeleteKind>,
+    set: Option<String>,
+    filter: Option<String>,
+    restore: Option<String>,
+}
+
+impl FromMeta for SoftDeleteArgs {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Ok(Self {
+            column: Identifier::from_string(value)?,
+            kind: SoftDeleteKind::Timestamp,
+        })
+    }
+
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        let options = SoftDeleteOptions::from_list(items)?;
+
+        let kind = match (options.kind, options.set, options.filter, options.restore) {
+            (kind, None, None, None) => kind.unwrap_or(SoftDeleteKind::Timestamp),
+            (None, Some(set), Some(filter), Some(restore)) => SoftDeleteKind::Custom {
+                set,
+                filter,
+                restore,
+            },
+            (Some(_), _, _, _) => {
+                return Err(darling::Error::custom(
+                    "The `kind` can not be combined with `set`, `filter` and `restore`",
+                ));
+            }
+            (None, _, _, _) => {
+                return Err(darling::Error::custom(
+                    "Custom soft deletes should specify `set`, `filter` and `restore`",
+                ));
+            }
+        };
+
+        Ok(Self {
+            column: options.column,
+            kind,
+        })
+    }
+}
+
+/// The soft delete column of an entity, and the expressions to use it in queries
+#[derive(Debug, Clone)]
+pub(crate) struct SoftDeleteCtx {
+    pub(crate) column: String,
+    pub(crate) kind: SoftDeleteKind,
+    column_sql: String,
+}
+
+impl SoftDeleteCtx {
+    pub(crate) fn new(args: SoftDeleteArgs, quote_identifiers: bool) -> Self {
+        Self {
+            column_sql: ident::quote(&args.column.0, quote_identifiers),
+            column: args.column.0,
+            kind: args.kind,
+        }
+    }
+
+    /// The column name, quoted for usage in a query
+    pub(crate) fn column_sql(&self) -> &str {
+        &self.column_sql
+    }
+
+    /// The condition matching the rows which are not soft deleted
+    pub(crate) fn filter_sql(&self) -> String {
+        let column = &self.column_sql;
+
+        match &self.kind {
+            SoftDeleteKind::Timestamp => format!("{column} IS NULL"),
+            SoftDeleteKind::Bool => format!("{column} IS NOT TRUE"),
+            SoftDeleteKind::Custom { filter, .. } => format!("({filter})"),
+        }
+    }
+
+    /// The condition matching the rows which are soft deleted
+    pub(crate) fn deleted_sql(&self) -> String {
+        let column = &self.column_sql;
+
+        match &self.kind {
+            SoftDeleteKind::Timestamp => format!("{column} IS NOT NULL"),
+            SoftDeleteKind::Bool => format!("{column} IS TRUE"),
+            // A `NULL` filter is not deleted either, so `NOT` would leave these rows in neither set
+            SoftDeleteKind::Custom { filter, .. } => format!("({filter}) IS NOT TRUE"),
+        }
+    }
+
+    /// The assignment marking a row as soft deleted
+    pub(crate) fn delete_sql(&self) -> String {
+        let column = &self.column_sql;
+
+        match &self.kind {
+            SoftDeleteKind::Timestamp => format!("{column} = NOW()"),
+            SoftDeleteKind::Bool => format!("{column} = TRUE"),
+            SoftDeleteKind::Custom { set, .. } => format!("{column} = {set}"),
+        }
+    }
+
+    /// The assignment restoring a soft deleted row
+    pub(crate) fn restore_sql(&self) -> String {
+        let column = &self.column_sql;
+
+        match &self.kind {
+            SoftDeleteKind::Timestamp => format!("{column} = NULL"),
+            SoftDeleteKind::Bool => format!("{column} = FALSE"),
+            SoftDeleteKind::Custom { restore, .. } => format!("{column} = {restore}"),
+        }
+    }
+}
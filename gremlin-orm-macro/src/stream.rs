@@ -6,18 +6,35 @@ pub(crate) fn generate_stream(args: &EntityCtx) -> TokenStream {
     let ident = args.ident.clone();
     let table = args.table.clone();
 
-    let columns = args.columns().collect::<Vec<_>>().join(", ");
+    let columns = if args.checked {
+        args.columns().collect::<Vec<_>>().join(", ")
+    } else {
+        args.runtime_columns().collect::<Vec<_>>().join(", ")
+    };
 
     let query = if let Some(soft_delete) = &args.soft_delete {
-        format!("SELECT {columns} FROM {table} WHERE {soft_delete} IS NULL")
+        format!(
+            "SELECT {columns} FROM {table} WHERE {filter}",
+            filter = soft_delete.filter_sql()
+        )
     } else {
         format!("SELECT {columns} FROM {table}")
     };
 
+    let fetch = if args.checked {
+        quote::quote! {
+            ::sqlx::query_as!(Self, #query).fetch::<'_, 'a>(executor)
+        }
+    } else {
+        quote::quote! {
+            ::sqlx::query_as::<_, Self>(#query).fetch::<'_, 'a>(executor)
+        }
+    };
+
     let stream = quote::quote! {
         impl ::gremlin_orm::StreamableEntity for #ident {
-            fn stream<'a>(executor: impl ::sqlx::PgExecutor<'a> + 'a) -> impl ::gremlin_orm::Stream<Item = Result<Self, ::sqlx::Error>> {
-                ::sqlx::query_as!(Self, #query).fetch::<'_, 'a>(executor)
+            fn stream<'a>(executor: impl ::sqlx::PgExecutor<'a> + 'a) -> impl ::gremlin_orm::Stream<Item = Result<Self, ::gremlin_orm::Error>> {
+                ::gremlin_orm::__private::TryStreamExt::map_err(#fetch, ::gremlin_orm::Error::from)
             }
         }
     };
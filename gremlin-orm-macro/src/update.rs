@@ -3,18 +3,7 @@ use proc_macro2::TokenStream;
 use crate::EntityCtx;
 
 pub(crate) fn generate_update(args: &EntityCtx) -> TokenStream {
-    let base = args
-        .data
-        .clone()
-        .into_iter()
-        .filter(|field| {
-            (field.pk || !field.generated)
-                && args
-                    .soft_delete
-                    .clone()
-                    .is_none_or(|soft_delete| field.ident != soft_delete)
-        })
-        .collect::<Vec<_>>();
+    let base = args.updatable().cloned().collect::<Vec<_>>();
 
     let entity_fields = base
         .iter()
@@ -44,35 +33,37 @@ pub(crate) fn generate_update(args: &EntityCtx) -> TokenStream {
 
     let mut query_where = base
         .iter()
-        .filter(|field| field.pk)
+        .filter(|field| field.is_key())
         .cloned()
         .enumerate()
         .map(|(idx, field)| {
-            let ident = field.ident;
+            let column = field.column_sql();
             let idx = idx + 1;
-            format!("{ident} = ${idx}")
+            format!("{column} = ${idx}")
         })
         .collect::<Vec<_>>();
 
-    let query_set = base
+    let mut query_set = base
         .iter()
-        .filter(|field| !field.pk)
+        .filter(|field| !field.is_key())
         .cloned()
         .enumerate()
         .map(|(idx, field)| {
-            let ident = field.ident;
+            let column = field.column_sql();
             let idx = idx + query_where.len() + 1;
-            format!("{ident} = ${idx}")
+            format!("{column} = ${idx}")
         })
         .collect::<Vec<_>>();
 
+    query_set.extend(args.update_set());
+
     if let Some(soft_delete) = &args.soft_delete {
-        query_where.push(format!("{soft_delete} IS NULL"));
+        query_where.push(soft_delete.filter_sql());
     }
 
     let values_ids = base
         .iter()
-        .filter(|field| field.pk)
+        .filter(|field| field.is_key())
         .cloned()
         .map(|field| {
             let cast = field.cast();
@@ -96,7 +87,7 @@ pub(crate) fn generate_update(args: &EntityCtx) -> TokenStream {
 
     let values_fields = base
         .iter()
-        .filter(|field| !field.pk)
+        .filter(|field| !field.is_key())
         .cloned()
         .map(|field| {
             let cast = field.cast();
@@ -131,13 +122,68 @@ pub(crate) fn generate_update(args: &EntityCtx) -> TokenStream {
 
     let table = args.table.clone();
 
+    let columns = if args.checked {
+        args.columns().collect::<Vec<_>>()
+    } else {
+        args.runtime_columns().collect::<Vec<_>>()
+    };
+
+    let query_where = query_where.join(" AND ");
+    let query_set = query_set.join(", ");
+
     let query = format!(
         "UPDATE {table} SET {query_set} WHERE {query_where} RETURNING {columns}",
-        query_where = query_where.join(" AND "),
-        query_set = query_set.join(", "),
-        columns = args.columns().collect::<Vec<_>>().join(", ")
+        columns = columns.join(", ")
     );
 
+    // The guard is appended to the conditions at runtime, so its placeholders follow the values
+    let guarded_query = format!("UPDATE {table} SET {query_set} WHERE {query_where} AND (");
+
+    // Without a matching row the version is stale, as the primary key(s) refer to an existing
+    // entity when it was fetched
+    let fetch = if args.version().is_some() {
+        quote::quote! { fetch_optional }
+    } else {
+        quote::quote! { fetch_one }
+    };
+
+    let update = if args.checked {
+        quote::quote! {
+            ::sqlx::query_as!(
+                #source_ident,
+                #query,
+                #(#values_ids),*,
+                #(#values_fields),*
+            ).#fetch(executor).await?
+        }
+    } else {
+        let values = base
+            .iter()
+            .filter(|field| field.is_key())
+            .chain(base.iter().filter(|field| !field.is_key()))
+            .map(|field| field.runtime_value());
+
+        quote::quote! {
+            ::sqlx::query_as::<_, Self::SourceEntity>(#query)
+                #(.bind(#values))*
+                .#fetch(executor)
+                .await?
+        }
+    };
+
+    let guarded_values = base
+        .iter()
+        .filter(|field| field.is_key())
+        .chain(base.iter().filter(|field| !field.is_key()))
+        .map(|field| field.runtime_value())
+        .collect::<Vec<_>>();
+
+    let result = if args.version().is_some() {
+        quote::quote! { #update.ok_or(::gremlin_orm::Error::StaleEntity) }
+    } else {
+        quote::quote! { Ok(#update) }
+    };
+
     let stream = quote::quote! {
         #vis struct #ident {
             #(#entity_fields),*
@@ -154,13 +200,31 @@ pub(crate) fn generate_update(args: &EntityCtx) -> TokenStream {
         impl ::gremlin_orm::UpdatableEntity for #ident {
             type SourceEntity = #source_ident;
 
-            async fn update<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Self::SourceEntity, ::sqlx::Error> {
-                ::sqlx::query_as!(
-                    #source_ident,
-                    #query,
-                    #(#values_ids),*,
-                    #(#values_fields),*
-                ).fetch_one(executor).await
+            async fn update<'a>(&self, executor: impl ::sqlx::PgExecutor<'a>) -> Result<Self::SourceEntity, ::gremlin_orm::Error> {
+                #result
+            }
+
+            async fn update_if<'a>(
+                &self,
+                guard: impl Into<::gremlin_orm::Filter<Self::SourceEntity>>,
+                executor: impl ::sqlx::PgExecutor<'a>,
+            ) -> Result<Option<Self::SourceEntity>, ::gremlin_orm::Error> {
+                let mut query = #guarded_query.to_string();
+                let mut arguments = ::sqlx::postgres::PgArguments::default();
+
+                #(::sqlx::Arguments::add(&mut arguments, #guarded_values).map_err(::sqlx::Error::Encode)?;)*
+
+                guard.into().render(&mut query, &mut arguments)?;
+                query.push_str(") RETURNING ");
+                query.push_str(<Self::SourceEntity as ::gremlin_orm::QueryableEntity>::COLUMNS);
+
+                let row = ::sqlx::query_with(::sqlx::AssertSqlSafe(query), arguments)
+                    .fetch_optional(executor)
+                    .await?;
+
+                Ok(row
+                    .map(|row| <Self::SourceEntity as ::gremlin_orm::QueryableEntity>::decode_row(&row))
+                    .transpose()?)
             }
         }
     };
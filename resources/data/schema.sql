@@ -47,7 +47,8 @@ CREATE TABLE public.release (
 
 CREATE TABLE public.artist_release (
 	artist_id INTEGER NOT NULL,
-	release_id INTEGER NOT NULL
+	release_id INTEGER NOT NULL,
+	PRIMARY KEY (artist_id, release_id)
 );
 
 CREATE TABLE public.artist_type (
@@ -66,7 +67,7 @@ CREATE TABLE public.defaultable (
 CREATE TYPE public.mood AS ENUM ('sad', 'ok', 'happy');
 
 CREATE TABLE public.person (
-    name text NOT NULL,
+    name text NOT NULL PRIMARY KEY,
     current_mood public.mood NOT NULL
 );
 
@@ -81,3 +82,76 @@ CREATE TABLE public.soft_delete (
 	value INTEGER NOT NULL,
 	deleted_at TIMESTAMP DEFAULT NULL
 );
+
+CREATE TABLE public.legacy_account (
+	"accountId" INTEGER NOT NULL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+	"displayName" TEXT NOT NULL,
+	"EMAIL" TEXT
+);
+
+CREATE TABLE public."Order" (
+	id INTEGER NOT NULL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+	"user" TEXT NOT NULL,
+	"group" TEXT NOT NULL
+);
+
+CREATE TABLE public.tag (
+	id INTEGER NOT NULL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+	name TEXT NOT NULL UNIQUE,
+	color TEXT NOT NULL DEFAULT 'black'
+);
+
+CREATE TABLE public.track (
+	id INTEGER NOT NULL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+	release_id INTEGER REFERENCES public.release (id),
+	name TEXT NOT NULL
+);
+
+CREATE TABLE public.credit (
+	id INTEGER NOT NULL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+	artist_id INTEGER NOT NULL,
+	release_id INTEGER NOT NULL,
+	role TEXT NOT NULL
+);
+
+CREATE TABLE public.document (
+	id INTEGER NOT NULL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+	title TEXT NOT NULL,
+	version INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE TABLE public.post (
+	id INTEGER NOT NULL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+	title TEXT NOT NULL,
+	created_at TIMESTAMP NOT NULL,
+	updated_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE public.flag_soft_delete (
+	id INTEGER NOT NULL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+	value INTEGER NOT NULL,
+	is_deleted BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE TABLE public.status_soft_delete (
+	id INTEGER NOT NULL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+	value INTEGER NOT NULL,
+	status TEXT NOT NULL DEFAULT 'active'
+);
+
+CREATE TABLE public.nullable_status_soft_delete (
+	id INTEGER NOT NULL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+	value INTEGER NOT NULL,
+	status TEXT DEFAULT 'active'
+);
+
+CREATE TABLE public.log_line (
+	message TEXT NOT NULL,
+	level INTEGER NOT NULL CHECK (level >= 0)
+);
+
+CREATE TABLE public.setting (
+	name TEXT NOT NULL PRIMARY KEY,
+	value TEXT NOT NULL,
+	version INTEGER NOT NULL DEFAULT 1
+);
@@ -0,0 +1,136 @@
+use sqlx::{
+    Encode, Postgres,
+    encode::IsNull,
+    postgres::{PgArgumentBuffer, PgConnection},
+};
+
+use crate::Error;
+
+/// Signature, flags and header extension length of the binary copy format
+const HEADER: &[u8] = b"PGCOPY\n\xff\r\n\0\0\0\0\0\0\0\0\0";
+
+/// Marks the end of the binary copy data
+const TRAILER: &[u8] = &(-1_i16).to_be_bytes();
+
+/// Microseconds between the Unix epoch and the PostgreSQL epoch of 2000-01-01
+const POSTGRES_EPOCH_MICROS: i64 = 946_684_800_000_000;
+
+/// The amount of buffered data after which it is sent to the database
+const SEND_THRESHOLD: usize = 64 * 1024;
+
+/// Buffer for data in the binary copy format
+#[doc(hidden)]
+pub struct CopyBuffer {
+    buf: PgArgumentBuffer,
+    now: i64,
+}
+
+impl CopyBuffer {
+    /// Create a buffer encoding `now`, in microseconds since the Unix epoch, as the timestamp of
+    /// the copy
+    pub(crate) fn new(now: i64) -> Self {
+        let mut buf = PgArgumentBuffer::default();
+        buf.extend_from_slice(HEADER);
+
+        Self {
+            buf,
+            now: now - POSTGRES_EPOCH_MICROS,
+        }
+    }
+
+    /// Start a new row containing the given number of fields
+    pub fn start_row(&mut self, fields: i16) {
+        self.buf.extend_from_slice(&fields.to_be_bytes());
+    }
+
+    /// Encode a field of the current row, using the same representation as when binding it to a
+    /// query
+    pub fn encode<'q, T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Encode<'q, Postgres>,
+    {
+        let offset = self.buf.len();
+        self.buf.extend_from_slice(&[0; 4]);
+
+        let is_null = value
+            .encode_by_ref(&mut self.buf)
+            .map_err(sqlx::Error::Encode)?;
+
+        let len = match is_null {
+            IsNull::No => i32::try_from(self.buf.len() - offset - 4)
+                .map_err(|err| sqlx::Error::Encode(Box::new(err)))?,
+            IsNull::Yes => -1,
+        };
+
+        self.buf[offset..offset + 4].copy_from_slice(&len.to_be_bytes());
+
+        Ok(())
+    }
+
+    /// Encode the `NOW()` of the database when the copy started as a field of the current row, in
+    /// the binary format of both `TIMESTAMP` and `TIMESTAMPTZ` columns. `TIMESTAMP` columns
+    /// receive the time in UTC.
+    pub fn encode_now(&mut self) {
+        self.buf.extend_from_slice(&8_i32.to_be_bytes());
+        self.buf.extend_from_slice(&self.now.to_be_bytes());
+    }
+
+    /// Fail encoding the current row
+    pub fn unsupported(&self, message: &'static str) -> Error {
+        Error::Database(sqlx::Error::Encode(message.into()))
+    }
+
+    pub(crate) fn should_send(&self) -> bool {
+        self.buf.len() >= SEND_THRESHOLD
+    }
+
+    /// Take the buffered data, leaving the buffer empty
+    pub(crate) fn take(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.buf).to_vec()
+    }
+
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        self.buf.extend_from_slice(TRAILER);
+        self.take()
+    }
+}
+
+/// Copy the encoded rows into the table using the binary format
+pub(crate) async fn copy_in<T, F>(
+    connection: &mut PgConnection,
+    statement: &str,
+    values: impl futures::Stream<Item = T>,
+    encode: F,
+) -> Result<u64, Error>
+where
+    F: Fn(&T, &mut CopyBuffer) -> Result<(), Error>,
+{
+    use futures::StreamExt;
+
+    // COPY can not use `NOW()`, so it is fetched up front to use the clock of the database, and
+    // the same timestamp for all rows of the copy
+    let now: i64 = sqlx::query_scalar("SELECT (EXTRACT(EPOCH FROM NOW()) * 1000000)::BIGINT")
+        .fetch_one(&mut *connection)
+        .await?;
+
+    let mut copy = connection.copy_in_raw(statement).await?;
+    let mut buf = CopyBuffer::new(now);
+
+    let mut values = std::pin::pin!(values);
+
+    while let Some(value) = values.next().await {
+        if let Err(err) = encode(&value, &mut buf) {
+            // Aborting returns the error the database responds with, which is not of interest
+            let _ = copy.abort(err.to_string()).await;
+            return Err(err);
+        }
+
+        if buf.should_send() {
+            copy.send(buf.take()).await?;
+        }
+    }
+
+    copy.send(buf.finish()).await?;
+
+    Ok(copy.finish().await?)
+}
@@ -0,0 +1,88 @@
+use sqlx::PgExecutor;
+
+use crate::{
+    Error, QueryableEntity,
+    query::{Filter, Query, Sql},
+};
+
+/// A deletion of the entities matching a filter, created using [`QueryableEntity::delete_where`]
+/// or [`PageableEntity::delete_many`](crate::PageableEntity::delete_many).
+///
+/// The entities are deleted using a single statement. If the entity uses soft delete, the
+/// matching rows are soft deleted instead, and rows which are already soft deleted are left
+/// untouched.
+pub struct Delete<E> {
+    query: Query<E>,
+}
+
+impl<E> Clone for Delete<E> {
+    fn clone(&self) -> Self {
+        Self {
+            query: self.query.clone(),
+        }
+    }
+}
+
+impl<E: QueryableEntity> Delete<E> {
+    pub(crate) fn new(filter: Filter<E>) -> Self {
+        Self {
+            query: Query::new().filter(filter),
+        }
+    }
+
+    /// Only delete rows which also match the filter. Multiple filters are combined using `AND`.
+    pub fn filter(mut self, filter: impl Into<Filter<E>>) -> Self {
+        self.query = self.query.filter(filter);
+        self
+    }
+
+    fn statement(&self) -> Sql {
+        let sql = match E::SOFT_DELETE_SET {
+            Some(set) => Sql::new(format!("UPDATE {} SET {set}", E::TABLE)),
+            None => Sql::new(format!("DELETE FROM {}", E::TABLE)),
+        };
+
+        sql.append(self.query.where_clause())
+    }
+
+    /// Delete the matching entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the amount of deleted entities, or an [`Error`].
+    pub async fn execute<'a>(&self, executor: impl PgExecutor<'a>) -> Result<u64, Error> {
+        let (sql, arguments) = self.statement().build()?;
+
+        Ok(sqlx::query_with(sqlx::AssertSqlSafe(sql), arguments)
+            .execute(executor)
+            .await?
+            .rows_affected())
+    }
+
+    /// Delete the matching entities, returning them as they were deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the deleted entities, or an [`Error`].
+    pub async fn fetch_all<'a>(&self, executor: impl PgExecutor<'a>) -> Result<Vec<E>, Error> {
+        let (sql, arguments) = self
+            .statement()
+            .push(format!(" RETURNING {}", E::COLUMNS))
+            .build()?;
+
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), arguments)
+            .fetch_all(executor)
+            .await?
+            .iter()
+            .map(|row| E::decode_row(row).map_err(Error::from))
+            .collect()
+    }
+}
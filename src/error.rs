@@ -0,0 +1,109 @@
+use sqlx::postgres::PgDatabaseError;
+
+/// Errors returned by the operations of the entities.
+///
+/// Failures reported by PostgreSQL which callers commonly need to handle, like constraint
+/// violations, are classified into their own variants. All other errors are wrapped in
+/// [`Error::Database`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// The entity could not be found, or is soft deleted
+    #[error("The entity could not be found")]
+    NotFound,
+    /// The entity was updated or deleted since it was fetched, as its version no longer matches
+    #[error("The entity is stale")]
+    StaleEntity,
+    /// A page was requested with a limit of 0, which can not tell whether more entities follow,
+    /// or entities were claimed with a limit of 0
+    #[error("The limit of a page or claim should be at least 1")]
+    InvalidPageLimit,
+    /// A unique or primary key constraint was violated (`23505`)
+    #[error("Unique constraint violated: {source}")]
+    UniqueViolation {
+        /// The name of the violated constraint
+        constraint: Option<String>,
+        /// The error reported by the database
+        source: sqlx::Error,
+    },
+    /// A foreign key constraint was violated (`23503`)
+    #[error("Foreign key constraint violated: {source}")]
+    ForeignKeyViolation {
+        /// The name of the violated constraint
+        constraint: Option<String>,
+        /// The error reported by the database
+        source: sqlx::Error,
+    },
+    /// A not null constraint was violated (`23502`)
+    #[error("Not null constraint violated: {source}")]
+    NotNullViolation {
+        /// The name of the column which was null
+        column: Option<String>,
+        /// The error reported by the database
+        source: sqlx::Error,
+    },
+    /// A check constraint was violated (`23514`)
+    #[error("Check constraint violated: {source}")]
+    CheckViolation {
+        /// The name of the violated constraint
+        constraint: Option<String>,
+        /// The error reported by the database
+        source: sqlx::Error,
+    },
+    /// The transaction could not be serialized, and should be retried (`40001`)
+    #[error("Could not serialize access: {0}")]
+    SerializationFailure(#[source] sqlx::Error),
+    /// A deadlock was detected, and the transaction should be retried (`40P01`)
+    #[error("Deadlock detected: {0}")]
+    Deadlock(#[source] sqlx::Error),
+    /// Any other error returned by SQLx
+    #[error("{0}")]
+    Database(#[source] sqlx::Error),
+}
+
+impl Error {
+    /// Whether the transaction failed due to concurrent transactions, and should be retried.
+    /// These are retried automatically by [`transaction`](crate::transaction).
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::SerializationFailure(_) | Self::Deadlock(_))
+    }
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(value: sqlx::Error) -> Self {
+        let sqlx::Error::Database(err) = &value else {
+            return match value {
+                sqlx::Error::RowNotFound => Self::NotFound,
+                value => Self::Database(value),
+            };
+        };
+
+        let Some(err) = err.try_downcast_ref::<PgDatabaseError>() else {
+            return Self::Database(value);
+        };
+
+        let constraint = err.constraint().map(ToString::to_string);
+
+        match err.code() {
+            "23505" => Self::UniqueViolation {
+                constraint,
+                source: value,
+            },
+            "23503" => Self::ForeignKeyViolation {
+                constraint,
+                source: value,
+            },
+            "23502" => Self::NotNullViolation {
+                column: err.column().map(ToString::to_string),
+                source: value,
+            },
+            "23514" => Self::CheckViolation {
+                constraint,
+                source: value,
+            },
+            "40001" => Self::SerializationFailure(value),
+            "40P01" => Self::Deadlock(value),
+            _ => Self::Database(value),
+        }
+    }
+}
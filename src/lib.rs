@@ -36,8 +36,24 @@
 //!
 //! ### Struct-level Annotations
 //!
-//! - `#[orm(table = "schema.table")]`: Specifies the database table for the entity.
-//! - `#[orm(soft_delete = "column_name")]`: Enables soft delete support for the entity. The given column (typically an `Option<chrono::NaiveDateTime>`) will be set to the current timestamp instead of deleting the row. Entities with a non-NULL value in this column are considered deleted and will be excluded from fetch, stream, and update operations.
+//! - `#[orm(table = "schema.table")]`: Specifies the database table for the entity. The schema is optional, and both parts may be double-quoted to preserve their case (e.g. `public."Order"`). Malformed table names are rejected at compile time.
+//! - `#[orm(soft_delete = "column_name")]`: Enables soft delete support for the entity using a timestamp column (typically an `Option<chrono::NaiveDateTime>`), which is set to `NOW()` instead of deleting the row. Rows with a non-NULL value in this column are considered deleted. For every kind of soft delete, deleted rows are excluded from fetch, stream, and update operations.
+//! - `#[orm(soft_delete(column = "is_deleted", kind = "bool"))]`: Enables soft delete support using a boolean column, which is set to `TRUE` on delete. Rows where the column is not `TRUE` are not deleted. The `kind` defaults to `timestamp`, which behaves like `soft_delete = "column_name"`.
+//! - `#[orm(soft_delete(column = "status", set = "'deleted'", filter = "status <> 'deleted'", restore = "'active'"))]`: Enables soft delete support using custom SQL expressions. The column is set to `set` on delete and to `restore` on restore, while `filter` is the condition matching the rows which are not deleted. Rows for which `filter` is `NULL` are considered deleted.
+//! - `#[orm(rename_all = "camelCase")]`: Derives the column names from the field names using the given rule. Supported rules are `lowercase`, `UPPERCASE`, `PascalCase`, `camelCase`, `snake_case`, `SCREAMING_SNAKE_CASE`, `kebab-case` and `SCREAMING-KEBAB-CASE`.
+//! - `#[orm(quote_identifiers = false)]`: Disables quoting of identifiers in the generated queries. By default the table and all columns are double-quoted, so reserved words like `user` or `order` can be used as names. With quoting disabled the table is used as written, and columns are only quoted when they would otherwise be folded to lowercase.
+//! - `#[orm(checked = false)]`: Generates queries which are checked at runtime instead of at compile time, so no database (or prepared `.sqlx` cache) is required to compile the entity. The entity should implement `sqlx::FromRow`. Enabling the `runtime-checked` feature uses this mode for all entities, unless `checked = true` is set.
+//! - `#[orm(conflict(fields(name, ...)))]` or `#[orm(conflict(constraint = "name"))]`: Sets the conflict target used by [`UpsertableEntity`], either a list of fields or the name of a unique constraint. Defaults to the primary key(s).
+//! - `#[orm(belongs_to(Artist, fk = "artist_id"))]`: Generates a loader method returning the `Artist` the entity refers to, like `release.artist(executor)`. The `fk` fields of the entity refer to the `references` fields of the related entity, which default to `{entity}_id` and `id`. Both accept a comma separated list of fields for composite keys. Can be used multiple times.
+//! - `#[orm(has_many(Track, fk = "release_id"))]`: Generates a loader method returning the `Track`s which refer to the entity, like `release.tracks(executor)`. The `fk` fields of the related entity refer to the `references` fields of the entity, which default to `{entity}_id` and the primary key(s). Can be used multiple times.
+//!
+//! The name of the loader method defaults to the snake cased name of the related entity, with an `s` appended for `has_many`, and can be set using `name = "..."`. As appending an `s` does not pluralize names ending in `s` or `y`, like `Category` or `Address`, `has_many` relations to these entities should set the `name`. Loaders use the queries of the related entity, so soft deleted entities are not loaded.
+//!
+//! The related entities of many entities can be loaded at once using
+//! [`QueryableEntity::load_related`], like `Release::load_related::<Track>(&releases, executor)`.
+//! When there are multiple relations to the same entity, the first one is used.
+//!
+//! - `#[orm(join(left = Artist, right = Release))]`: Implements [`JoinEntity`] for a join table linking two entities. The `left_fk` and `right_fk` fields default to `{entity}_id`, and refer to the `left_pk` and `right_pk` fields of the "Pk" structs, which default to `id`. Join entities can not be soft deleted.
 //!
 //! ### Field-level Annotations
 //!
@@ -47,6 +63,36 @@
 //! - `#[orm(as_ref)]`: Used for optional primitive types (e.g., `Option<i32>`, `Option<bool>`), calling `.as_ref()` instead of `.as_deref()` when generating update queries. This is useful for optional primitive values that don't need dereferencing.
 //! - `#[orm(default)]`: Allows the field to use a default value when inserting, by wrapping it in `Defaultable<T>`.
 //! - `#[orm(cast = "Type")]`: Casts the field to the specified SQL type in generated queries. This is useful when you want to explicitly cast a column in SQL (e.g., for custom types or to resolve type mismatches).
+//! - `#[orm(column = "name")]`: Maps the field to the given column, instead of the column with the same name as the field. Takes precedence over `rename_all`.
+//! - `#[orm(version)]`: Uses the field (typically an integer) for optimistic locking. Updates only match the row when the version is unchanged, and increment it. Upserts updating an existing row increment it as well. When no row matches, [`Error::StaleEntity`] is returned. Typically combined with `default`, to let the database set the initial version.
+//! - `#[orm(created_at)]` and `#[orm(updated_at)]`: Marks the field as an audit timestamp, which is excluded from the "Insertable" and "Updatable" structs. Both are set to `NOW()` on insert, and `updated_at` is also set to `NOW()` on update and upsert. As `COPY` can not use `NOW()`, `copy_in` fetches `NOW()` from the database when the copy starts and writes it to every row, in UTC for `TIMESTAMP` columns. The columns should be of type `TIMESTAMP` or `TIMESTAMPTZ`.
+//! - `#[orm(belongs_to = Artist)]`: Like the struct-level `belongs_to`, using the annotated field as foreign key.
+//!
+//! ## Errors
+//!
+//! All operations return a [`gremlin_orm::Error`](Error), which classifies common PostgreSQL
+//! failures like unique and foreign key violations into their own variants. SQLx errors convert
+//! into it, so `?` can be used on raw SQLx queries in the same function.
+//!
+//! ## Transactions
+//!
+//! All operations accept any executor, so they can be composed within a transaction. Use
+//! [`transaction`] to run a closure within a transaction, which is committed when the closure
+//! succeeds and retried on serialization failures and deadlocks:
+//!
+//! ```rust,ignore
+//! let options = TransactionOptions::new(IsolationLevel::Serializable).retries(5);
+//!
+//! let artist = gremlin_orm::transaction(&pool, options, async |conn| {
+//!     let artist = ArtistPk { id: 1 }.fetch_for_update(&mut *conn).await?;
+//!     // ...
+//!     Ok(artist)
+//! })
+//! .await?;
+//! ```
+//!
+//! Within the closure, [`savepoint`] runs a nested closure which can fail without rolling back
+//! the rest of the transaction.
 //!
 //! ## Traits Overview
 //!
@@ -57,38 +103,223 @@
 //! Fields annotated with the default annotation are wrapped in [`Defaultable`], indicating if the
 //! default value should be used, or the provided one.
 //!
+//! Multiple entities can be inserted at once using `insert_many`, or `insert_many_stream` for a
+//! stream of values. These insert the values in batches of multi-row statements within a
+//! transaction, respecting the default of each [`Defaultable`] value.
+//!
+//! ### [`UpsertableEntity`]
+//!
+//! For "Insertable" structs that can be inserted while resolving conflicts with existing rows,
+//! using `INSERT ... ON CONFLICT`. `upsert` updates the conflicting row with the inserted values,
+//! while `insert_or_ignore` leaves it untouched and returns `None`.
+//!
+//! The conflict target defaults to the primary key(s), and can be changed with the `conflict`
+//! annotation. Entities without primary keys and a conflict target do not implement this trait.
+//!
+//! ### [`CopyableEntity`]
+//!
+//! For "Insertable" structs that can be copied into the database in bulk using `copy_in`, which
+//! streams the values in the binary `COPY` format. This is intended for loading large amounts of
+//! rows, where even batched inserts are too slow.
+//!
 //! ### [`FetchableEntity`]
 //!
 //! For types that can be fetched by primary key(s) from the database. A "Pk" struct is generated
-//! for each entity, containing only the primary key fields.
+//! for each entity, containing only the primary key fields. Multiple entities can be fetched in a
+//! single query using `fetch_many`, or `fetch_many_map` to look them up by their primary key(s).
+//! Use `exists` to only check whether the entity exists.
 //!
-//! > If the entity uses soft delete, fetch operations will return `None` for rows where the soft delete column is set (i.e., not NULL).
+//! > If the entity uses soft delete, fetch operations will return `None` for rows which are soft deleted according to the soft delete filter.
+//!
+//! ### [`LockableEntity`]
+//!
+//! For "Pk" structs of entities with primary key(s). Use `fetch_for_update` or `fetch_for_share`
+//! within a transaction to lock the row of the entity until the end of the transaction, or
+//! `fetch_locked` with a [`Lock`] using `nowait` or `skip_locked` to not wait for rows locked by
+//! other transactions.
 //!
 //! ### [`StreamableEntity`]
 //!
 //! For types that can be streamed (selected) from the database. This trait is implemented for
 //! the entity struct, allowing you to stream all rows from the table.
 //!
-//! > If the entity uses soft delete, only rows where the soft delete column is NULL will be streamed.
+//! > If the entity uses soft delete, only rows matching the soft delete filter, which are not deleted, will be streamed.
+//!
+//! ### [`QueryableEntity`]
+//!
+//! For types that can be queried with filters, orderings and limits. A "Columns" struct is
+//! generated for each entity, containing a typed [`Column`] for each field, which are used to
+//! build the query:
+//!
+//! ```rust,ignore
+//! let columns = Artist::columns();
+//!
+//! let artists = Artist::query()
+//!     .filter(columns.name.like("The %").or(columns.id.lt(10)))
+//!     .order_by(columns.name.desc())
+//!     .limit(10)
+//!     .fetch_all(&pool)
+//!     .await?;
+//! ```
+//!
+//! Queries can also be used to `count` the matching entities, check whether any `exists`, or
+//! compute the `min`, `max` or `sum` of a column. The selected rows can be locked until the end
+//! of the transaction using [`Query::lock`].
+//!
+//! > If the entity uses soft delete, only rows matching the soft delete filter, which are not deleted, will be returned.
+//!
+//! Columns can also be changed atomically using `update_where`, which builds a single `UPDATE`
+//! returning the updated entities. Entities with primary key(s) can be updated by their "Pk":
+//!
+//! ```rust,ignore
+//! let columns = Release::columns();
+//!
+//! let release = Release::update_where(ReleasePk { id: 1 })
+//!     .append(columns.synonyms, "Alias")
+//!     .set(columns.name, "Updated")
+//!     .fetch_one(&pool)
+//!     .await?;
+//! ```
+//!
+//! > Updates using `update_where` will only affect rows that are not soft deleted.
+//!
+//! Similarly, `delete_where` deletes all entities matching a filter using a single statement.
+//! Both can be executed using `execute`, returning the amount of affected rows, or `fetch_all`,
+//! returning the affected entities.
+//!
+//! ### [`PageableEntity`]
+//!
+//! For entities with primary key(s), which can be paged through using `page_after`. Pages are
+//! ordered by the primary key(s), and the "Pk" of the last entity is returned as cursor for the
+//! next page. This keeps pages stable under concurrent inserts, unlike `OFFSET`.
+//!
+//! Multiple entities can be deleted by their primary key(s) using `delete_many`, like
+//! `Artist::delete_many(&pks).execute(&pool)`.
+//!
+//! Tables can be used as work queues using `claim_next`, which locks the next entities using
+//! `FOR UPDATE SKIP LOCKED`, so concurrent transactions claim different entities:
+//!
+//! ```rust,ignore
+//! let mut tx = pool.begin().await?;
+//!
+//! let jobs = Job::query()
+//!     .filter(Job::columns().status.eq("pending"))
+//!     .claim_next(10, &mut *tx)
+//!     .await?;
+//! ```
+//!
+//! > If the entity uses soft delete, only rows matching the soft delete filter, which are not deleted, will be returned.
+//!
+//! ### [`JoinEntity`]
+//!
+//! For join-table entities linking a left and right entity, like artists to their releases. Use
+//! `link` and `unlink` to add or remove a single link, `set_links` to replace all links of the
+//! left entity within a transaction, and `linked_right` or `linked_left` to fetch the linked
+//! entities.
+//!
+//! ```rust,ignore
+//! ArtistRelease::link(&(&artist).into(), &(&release).into(), &pool).await?;
+//!
+//! let releases = ArtistRelease::linked_right(&(&artist).into(), &pool).await?;
+//! ```
 //!
 //! ### [`UpdatableEntity`]
 //!
 //! For types that can be updated in the database. An "Updatable" struct is generated for each
-//! entity, containing the primary key(s) and updatable fields.
+//! entity, containing the primary key(s) and updatable fields. `update` returns
+//! [`Error::NotFound`] when the entity does not exist, while `try_update` returns `None`.
 //!
-//! > Updates will only affect rows that are not soft deleted (i.e., where the soft delete column is NULL).
+//! > Updates will only affect rows that are not soft deleted, according to the soft delete filter.
+//!
+//! The "Updatable" struct also contains the `version` field, if any, which should be the version
+//! of the entity as it was fetched.
+//!
+//! Use `update_if` to only update the entity when it matches a guard, which is either a
+//! [`Filter`] or [`RawSql`] with bound values. It returns `None` when the guard does not match:
+//!
+//! ```rust,ignore
+//! let columns = Job::columns();
+//!
+//! let started = UpdatableJob {
+//!     status: "running".to_string(),
+//!     ..UpdatableJob::from(job)
+//! }
+//! .update_if(columns.status.eq("pending"), &pool)
+//! .await?;
+//! ```
+//!
+//! ### [`PatchableEntity`]
+//!
+//! For partially updating an entity. A "Patch" struct is generated for each entity with primary
+//! key(s), containing the primary key(s), the `version` field if any, and an `Option` for each
+//! updatable field. Nullable fields use [`Nullable`] instead, to distinguish leaving the value
+//! unchanged from setting it to `NULL`. Only the present fields are updated by `patch`.
+//!
+//! ```rust,ignore
+//! let release = PatchRelease {
+//!     name: Some("Updated".to_string()),
+//!     ..ReleasePk { id: 1 }.into()
+//! }
+//! .patch(&pool)
+//! .await?;
+//! ```
+//!
+//! > Patches will only affect rows that are not soft deleted.
 //!
 //! ### [`DeletableEntity`]
 //!
 //! For types that can be deleted from the database. This trait is implemented for the entity
-//! struct, allowing you to delete a row by its primary key(s).
+//! struct, allowing you to delete a row by its primary key(s). Entities without primary keys do
+//! not implement this trait.
+//!
+//! Use `try_delete` to check whether the entity was deleted, or `delete_returning` to get the
+//! deleted row.
 //!
-//! > If the entity uses soft delete, calling `delete` will set the soft delete column to the current timestamp instead of removing the row from the database.
+//! > If the entity uses soft delete, calling `delete` will mark the row as deleted instead of removing it from the database: timestamp columns are set to `NOW()`, boolean columns to `TRUE`, and custom soft deletes to their `set` expression. Entities which are already soft deleted are left untouched.
 //!
+//! Use [`QueryableEntity::delete_where`] or [`PageableEntity::delete_many`] to delete multiple
+//! entities using a single statement, which are soft deleted in the same way.
+//!
+//! ### [`SoftDeletableEntity`]
+//!
+//! For entities using soft delete, with primary key(s) and the soft delete column as a field.
+//! Soft deleted entities can be brought back using `restore`, or removed from the database using
+//! `force_delete`. Use `fetch_with_deleted`, `stream_with_deleted` or `stream_only_deleted` to
+//! also select soft deleted entities, and `purge_deleted` to remove all soft deleted entities.
+//!
+//! Entities soft deleted using a timestamp also implement [`PurgeableEntity`], to remove all
+//! entities which were soft deleted before a timestamp using `purge_deleted_before`.
+//!
+//! Queries can also select soft deleted entities using [`Query::with_deleted`] or
+//! [`Query::only_deleted`].
+//!
+
+use std::{collections::HashMap, hash::Hash};
 
+pub use delete::Delete;
+pub use error::Error;
 pub use futures::Stream;
+use futures::{StreamExt, TryStreamExt};
 pub use gremlin_orm_macro::Entity;
-use sqlx::PgExecutor;
+pub use query::{
+    Bindable, Column, Filter, Lock, Order, Page, Query, RawSql, RowValue, RowValues, Summable,
+};
+use sqlx::{Acquire, PgExecutor, Postgres, Transaction, postgres::PgRow};
+pub use transaction::{IsolationLevel, TransactionOptions, savepoint, transaction};
+pub use update::{Appendable, Update};
+
+mod copy;
+mod delete;
+mod error;
+mod query;
+mod transaction;
+mod update;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::copy::CopyBuffer;
+    pub use futures::TryStreamExt;
+}
 
 /// Used for inserting values, use either the default or the provided value
 pub enum Defaultable<T> {
@@ -98,12 +329,30 @@ pub enum Defaultable<T> {
     Value(T),
 }
 
+/// Used for patching nullable values, either leave the value unchanged, set it to `NULL`, or set
+/// it to the provided value
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Nullable<T> {
+    /// Leave the value unchanged
+    #[default]
+    Unchanged,
+    /// Set the value to `NULL`
+    Null,
+    /// Set the value to the provided value
+    Value(T),
+}
+
 /// Trait for types that can be inserted into the database.
 /// An "Insertable" struct is generated for each entity, containing only the fields that should be provided on insert.
 pub trait InsertableEntity {
     /// The entity type returned after insertion (typically the main entity struct).
     type SourceEntity;
 
+    /// The maximum number of values inserted by a single statement, keeping the number of bind
+    /// parameters within the limit of PostgreSQL.
+    #[doc(hidden)]
+    const BATCH_SIZE: usize;
+
     /// Insert the entity into the database.
     ///
     /// # Arguments
@@ -112,11 +361,189 @@ pub trait InsertableEntity {
     ///
     /// # Returns
     ///
-    /// A future resolving to either the inserted entity or a SQLx error.
+    /// A future resolving to either the inserted entity or an [`Error`].
     fn insert<'a>(
         &self,
         executor: impl PgExecutor<'a>,
-    ) -> impl Future<Output = Result<Self::SourceEntity, sqlx::Error>>;
+    ) -> impl Future<Output = Result<Self::SourceEntity, Error>>;
+
+    /// Insert the values using a single statement. The number of values should not exceed
+    /// [`InsertableEntity::BATCH_SIZE`].
+    #[doc(hidden)]
+    fn insert_batch<'a>(
+        values: &[Self],
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Vec<Self::SourceEntity>, Error>>
+    where
+        Self: Sized;
+
+    /// Insert multiple entities into the database. The values are inserted in batches, each
+    /// using a single statement, within a transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The entities to insert.
+    /// * `executor` - The connection to run the queries on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the inserted entities, in the order of `values`, or an
+    /// [`Error`].
+    fn insert_many<'a>(
+        values: &[Self],
+        executor: impl Acquire<'a, Database = Postgres>,
+    ) -> impl Future<Output = Result<Vec<Self::SourceEntity>, Error>>
+    where
+        Self: Sized,
+    {
+        async move {
+            let mut tx = executor.begin().await?;
+            let mut inserted = Vec::with_capacity(values.len());
+
+            for batch in values.chunks(Self::BATCH_SIZE) {
+                inserted.extend(Self::insert_batch(batch, &mut *tx).await?);
+            }
+
+            tx.commit().await?;
+
+            Ok(inserted)
+        }
+    }
+
+    /// Insert a stream of entities into the database. The values are inserted in batches, each
+    /// using a single statement, within a transaction which is committed once the stream of
+    /// values is exhausted.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The entities to insert.
+    /// * `executor` - The connection to run the queries on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// An async stream of results, each being either an inserted entity or an [`Error`].
+    fn insert_many_stream<'a>(
+        values: impl Stream<Item = Self> + Unpin + 'a,
+        executor: impl Acquire<'a, Database = Postgres> + 'a,
+    ) -> impl Stream<Item = Result<Self::SourceEntity, Error>> + 'a
+    where
+        Self: Sized + 'a,
+        Self::SourceEntity: 'a,
+    {
+        let batches = values.chunks(Self::BATCH_SIZE);
+
+        futures::stream::try_unfold(
+            (batches, Some(executor), None::<Transaction<'a, Postgres>>),
+            |(mut batches, executor, tx)| async move {
+                let mut tx = match (tx, executor) {
+                    (Some(tx), _) => tx,
+                    (None, Some(executor)) => executor.begin().await?,
+                    (None, None) => unreachable!("Either a transaction or executor is present"),
+                };
+
+                match batches.next().await {
+                    Some(batch) => {
+                        let inserted = Self::insert_batch(&batch, &mut *tx).await?;
+
+                        Ok(Some((inserted, (batches, None, Some(tx)))))
+                    }
+                    None => {
+                        tx.commit().await?;
+
+                        Ok::<_, Error>(None)
+                    }
+                }
+            },
+        )
+        .map_ok(|inserted| futures::stream::iter(inserted.into_iter().map(Ok)))
+        .try_flatten()
+    }
+}
+
+/// Trait for types that can be inserted into the database, resolving conflicts with existing rows.
+/// This trait is implemented for the "Insertable" struct of each entity with a conflict target,
+/// which defaults to the primary key(s).
+pub trait UpsertableEntity {
+    /// The entity type returned after insertion (typically the main entity struct).
+    type SourceEntity;
+
+    /// Insert the entity into the database, or update the conflicting row with the inserted
+    /// values.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the inserted or updated entity, or an [`Error`].
+    fn upsert<'a>(
+        &self,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Self::SourceEntity, Error>>;
+
+    /// Insert the entity into the database, leaving the conflicting row untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either `Some(entity)` if inserted, `None` if a conflicting row
+    /// exists, or an [`Error`].
+    fn insert_or_ignore<'a>(
+        &self,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Option<Self::SourceEntity>, Error>>;
+}
+
+/// Trait for types that can be copied into the database in bulk, using the binary `COPY` format.
+/// This trait is implemented for the "Insertable" struct of each entity.
+pub trait CopyableEntity: Sized {
+    /// The `COPY ... FROM STDIN` statement for the table.
+    #[doc(hidden)]
+    const COPY_STATEMENT: &'static str;
+
+    /// Encode the entity as a row in the binary copy format.
+    #[doc(hidden)]
+    fn encode_row(&self, buf: &mut __private::CopyBuffer) -> Result<(), Error>;
+
+    /// Copy a stream of entities into the database, which is considerably faster than inserting
+    /// them for large amounts of rows.
+    ///
+    /// Each value is encoded the same way as when it is bound to a query, so the types of the
+    /// fields should match the types of the columns. Columns which are generated are left to the
+    /// database, but [`Defaultable::Default`] values are not supported. Columns of type `json`
+    /// (use `jsonb` instead) and arrays of custom types can not be copied.
+    ///
+    /// As `COPY` can not use `NOW()`, the `created_at` and `updated_at` fields are set to the
+    /// `NOW()` of the database when the copy starts, like when inserting in the same transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The entities to copy into the table.
+    /// * `executor` - The connection to run the copy on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the number of copied rows, or an [`Error`].
+    fn copy_in<'a>(
+        values: impl Stream<Item = Self>,
+        executor: impl Acquire<'a, Database = Postgres>,
+    ) -> impl Future<Output = Result<u64, Error>> {
+        async move {
+            let mut connection = executor.acquire().await?;
+
+            copy::copy_in(
+                &mut connection,
+                Self::COPY_STATEMENT,
+                values,
+                Self::encode_row,
+            )
+            .await
+        }
+    }
 }
 
 /// Trait for types that can be fetched by primary key(s) from the database.
@@ -125,6 +552,13 @@ pub trait FetchableEntity {
     /// The entity type returned after fetching (typically the main entity struct).
     type SourceEntity;
 
+    /// The value of the primary key, or a tuple of the values for composite primary keys.
+    type Key;
+
+    /// The value(s) of the primary key(s) of the entity.
+    #[doc(hidden)]
+    fn key_of(entity: &Self::SourceEntity) -> Self::Key;
+
     /// Fetch the entity from the database by its primary key(s).
     ///
     /// # Arguments
@@ -133,11 +567,129 @@ pub trait FetchableEntity {
     ///
     /// # Returns
     ///
-    /// A future resolving to either `Some(entity)` if found, `None` if not found, or a SQLx error.
+    /// A future resolving to either `Some(entity)` if found, `None` if not found, or an [`Error`].
     fn fetch<'a>(
         &self,
         executor: impl PgExecutor<'a>,
-    ) -> impl Future<Output = Result<Option<Self::SourceEntity>, sqlx::Error>>;
+    ) -> impl Future<Output = Result<Option<Self::SourceEntity>, Error>>;
+
+    /// Check whether the entity exists in the database, by its primary key(s).
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either whether the entity exists, or an [`Error`].
+    fn exists<'a>(
+        &self,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<bool, Error>>;
+
+    /// Fetch all entities matching the given primary keys, using a single query.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The primary keys of the entities to fetch.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the found entities in no particular order, or an [`Error`].
+    /// Keys which could not be found are skipped.
+    fn fetch_many<'a>(
+        keys: &[Self],
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Vec<Self::SourceEntity>, Error>>
+    where
+        Self: Sized;
+
+    /// Fetch all entities matching the given primary keys, like [`FetchableEntity::fetch_many`],
+    /// keyed by their primary key(s). This allows checking which keys could not be found.
+    ///
+    /// The entities are keyed by the value of their primary key, or a tuple of the values for
+    /// composite primary keys, which should implement `Eq` and `Hash`.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The primary keys of the entities to fetch.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the found entities by their primary key(s), or an [`Error`].
+    fn fetch_many_map<'a>(
+        keys: &[Self],
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<HashMap<Self::Key, Self::SourceEntity>, Error>>
+    where
+        Self: Sized,
+        Self::Key: Eq + Hash,
+    {
+        async move {
+            Ok(Self::fetch_many(keys, executor)
+                .await?
+                .into_iter()
+                .map(|entity| (Self::key_of(&entity), entity))
+                .collect())
+        }
+    }
+}
+
+/// Trait for "Pk" structs whose entity row can be locked while fetching it.
+/// Entities without primary keys do not implement this trait.
+pub trait LockableEntity: FetchableEntity {
+    /// Fetch the entity from the database by its primary key(s), locking its row until the end
+    /// of the transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `lock` - The lock to take, like [`Lock::update`] or [`Lock::share`].
+    /// * `executor` - The executor to run the query on, typically a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either `Some(entity)` if found, `None` if not found or skipped as
+    /// it is locked, or an [`Error`]. With [`Lock::nowait`], an error is returned when the row
+    /// is locked by another transaction.
+    fn fetch_locked<'a>(
+        &self,
+        lock: Lock,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Option<Self::SourceEntity>, Error>>;
+
+    /// Fetch the entity, locking its row using `FOR UPDATE` like [`LockableEntity::fetch_locked`].
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, typically a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either `Some(entity)` if found, `None` if not found, or an [`Error`].
+    fn fetch_for_update<'a>(
+        &self,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Option<Self::SourceEntity>, Error>> {
+        self.fetch_locked(Lock::update(), executor)
+    }
+
+    /// Fetch the entity, locking its row using `FOR SHARE` like [`LockableEntity::fetch_locked`].
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, typically a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either `Some(entity)` if found, `None` if not found, or an [`Error`].
+    fn fetch_for_share<'a>(
+        &self,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Option<Self::SourceEntity>, Error>> {
+        self.fetch_locked(Lock::share(), executor)
+    }
 }
 
 /// Trait for types that can be streamed (selected) from the database.
@@ -151,10 +703,307 @@ pub trait StreamableEntity: Sized {
     ///
     /// # Returns
     ///
-    /// An async stream of results, each being either the entity or a SQLx error.
-    fn stream<'a>(
-        executor: impl PgExecutor<'a> + 'a,
-    ) -> impl Stream<Item = Result<Self, sqlx::Error>>;
+    /// An async stream of results, each being either the entity or an [`Error`].
+    fn stream<'a>(executor: impl PgExecutor<'a> + 'a) -> impl Stream<Item = Result<Self, Error>>;
+}
+
+/// Trait for types that can be queried from the database, using filters, orderings and limits
+/// built from their columns. This trait is implemented for the entity struct, along with a
+/// "Columns" struct containing a [`Column`] for each field.
+pub trait QueryableEntity: Sized {
+    /// The columns of the entity (typically the generated "Columns" struct).
+    type Columns;
+
+    /// The table of the entity, as it is used in queries.
+    #[doc(hidden)]
+    const TABLE: &'static str;
+
+    /// The columns to select, in the order of the fields of the entity.
+    #[doc(hidden)]
+    const COLUMNS: &'static str;
+
+    /// The condition excluding soft deleted rows, if the entity uses soft delete.
+    #[doc(hidden)]
+    const SOFT_DELETE: Option<&'static str>;
+
+    /// The assignment marking a row as soft deleted, if the entity uses soft delete.
+    #[doc(hidden)]
+    const SOFT_DELETE_SET: Option<&'static str>;
+
+    /// The columns assigned by every update and their values, like setting the `updated_at`
+    /// timestamp.
+    #[doc(hidden)]
+    const ON_UPDATE: &'static [(&'static str, &'static str)];
+
+    /// Decode the entity from a row containing the selected columns.
+    #[doc(hidden)]
+    fn decode_row(row: &PgRow) -> Result<Self, sqlx::Error>;
+
+    /// The columns of the entity, used to build filters and orderings.
+    fn columns() -> Self::Columns;
+
+    /// Start a query selecting entities from the database table.
+    ///
+    /// # Returns
+    ///
+    /// A [`Query`] without any filters, which can be executed using [`Query::fetch_all`],
+    /// [`Query::fetch_optional`] or [`Query::stream`].
+    fn query() -> Query<Self> {
+        Query::new()
+    }
+
+    /// Start an update of the entities matching the filter, which changes columns using SQL
+    /// expressions like increments and appends. Entities with primary key(s) can also be updated
+    /// by their "Pk" struct, which converts into a filter.
+    ///
+    /// # Returns
+    ///
+    /// An [`Update`] without any changes, which can be executed using [`Update::fetch_all`],
+    /// [`Update::fetch_optional`] or [`Update::fetch_one`].
+    fn update_where(filter: impl Into<Filter<Self>>) -> Update<Self> {
+        Update::new(filter.into())
+    }
+
+    /// Start a deletion of the entities matching the filter, which deletes them using a single
+    /// statement. If the entity uses soft delete, the entities are soft deleted instead.
+    ///
+    /// # Returns
+    ///
+    /// A [`Delete`], which can be executed using [`Delete::execute`] or [`Delete::fetch_all`].
+    fn delete_where(filter: impl Into<Filter<Self>>) -> Delete<Self> {
+        Delete::new(filter.into())
+    }
+
+    /// Count the entities in the database table, excluding soft deleted rows. Use
+    /// [`Query::count`] to only count the entities matching a filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the amount of entities, or an [`Error`].
+    fn count<'a>(executor: impl PgExecutor<'a>) -> impl Future<Output = Result<i64, Error>> {
+        async move { Self::query().count(executor).await }
+    }
+
+    /// Load the related entities of each of the given entities, using a single query for all of
+    /// them instead of one per entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `entities` - The entities to load the related entities of.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the related entities of each entity, in the same order as the
+    /// given entities, or an [`Error`].
+    ///
+    /// Entities with more than one relation to the same entity can not tell which one to load,
+    /// and do not support loading that entity in bulk.
+    fn load_related<'a, T>(
+        entities: &[Self],
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Vec<Vec<T>>, Error>>
+    where
+        Self: Related<T>,
+        T: QueryableEntity,
+    {
+        async move { T::query().fetch_related(entities, executor).await }
+    }
+}
+
+/// Trait for entities with a relation to another entity, generated for the `belongs_to` and
+/// `has_many` annotations. Used by [`QueryableEntity::load_related`] to load the related entities
+/// of many entities at once.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no single relation to `{T}`",
+    note = "related entities can only be loaded in bulk with exactly one `belongs_to` or `has_many` relation to them"
+)]
+pub trait Related<T: QueryableEntity>: Sized {
+    /// The values of the fields linking the entities.
+    #[doc(hidden)]
+    type Key: Clone + Eq + Hash;
+
+    /// The key of the entity, or `None` if any of the fields is not set.
+    #[doc(hidden)]
+    fn key(&self) -> Option<Self::Key>;
+
+    /// The columns of the related entity which are matched against the key.
+    #[doc(hidden)]
+    fn key_columns() -> Vec<&'static str>;
+
+    /// Filter for related entities matching any of the keys.
+    #[doc(hidden)]
+    fn keys_filter(keys: Vec<Self::Key>) -> Filter<T>;
+
+    /// Decode the key from the last columns of the row.
+    #[doc(hidden)]
+    fn decode_key(row: &PgRow) -> Result<Self::Key, sqlx::Error>;
+}
+
+/// Trait for entities that can be paged through using their primary key(s) as cursor.
+/// This trait is implemented for the entity struct, if it has primary key(s).
+pub trait PageableEntity: QueryableEntity {
+    /// The primary key(s) of the entity (typically the generated "Pk" struct).
+    type Pk: for<'e> From<&'e Self>;
+
+    /// Filter for entities which come after the cursor.
+    #[doc(hidden)]
+    fn after(cursor: &Self::Pk) -> Filter<Self>;
+
+    /// Order by the primary key(s).
+    #[doc(hidden)]
+    fn key_order() -> Vec<Order<Self>>;
+
+    /// Filter for entities matching any of the primary key(s).
+    #[doc(hidden)]
+    fn keys_filter(pks: &[Self::Pk]) -> Filter<Self>;
+
+    /// Fetch a page of entities from the database table, ordered by their primary key(s). Use
+    /// [`Query::page_after`] to only page through the entities matching a filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor` - The cursor returned with the previous page, or `None` for the first page.
+    /// * `limit` - The maximum amount of entities on the page, which should be at least 1.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the page, or an [`Error`]. [`Error::InvalidPageLimit`] is
+    /// returned when the limit is 0.
+    fn page_after<'a>(
+        cursor: Option<Self::Pk>,
+        limit: u64,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Page<Self, Self::Pk>, Error>> {
+        async move { Self::query().page_after(cursor, limit, executor).await }
+    }
+
+    /// Claim the next entities, like taking jobs from a queue, ordered by their primary key(s).
+    /// Use [`Query::claim_next`] to only claim the entities matching a filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum amount of entities to claim, which should be at least 1.
+    /// * `executor` - The executor to run the query on, typically a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the claimed entities, which are locked until the end of the
+    /// transaction, or an [`Error`]. [`Error::InvalidPageLimit`] is returned when the limit is 0.
+    fn claim_next<'a>(
+        limit: u64,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Vec<Self>, Error>> {
+        async move { Self::query().claim_next(limit, executor).await }
+    }
+
+    /// Start a deletion of the entities with the given primary key(s), which deletes them using
+    /// a single statement.
+    ///
+    /// # Returns
+    ///
+    /// A [`Delete`], which can be executed using [`Delete::execute`] or [`Delete::fetch_all`].
+    fn delete_many(pks: &[Self::Pk]) -> Delete<Self> {
+        Delete::new(Self::keys_filter(pks))
+    }
+}
+
+/// Trait for join-table entities linking two other entities, generated for the `join`
+/// annotation. This trait is implemented for the entity struct.
+pub trait JoinEntity {
+    /// The entity on the left side of the link.
+    type Left: PageableEntity;
+
+    /// The entity on the right side of the link.
+    type Right: PageableEntity;
+
+    /// Link the entities, if they are not linked yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `left` - The primary key of the left entity.
+    /// * `right` - The primary key of the right entity.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either whether a link was created, or an [`Error`].
+    fn link<'a>(
+        left: &<Self::Left as PageableEntity>::Pk,
+        right: &<Self::Right as PageableEntity>::Pk,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<bool, Error>>;
+
+    /// Remove the link between the entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `left` - The primary key of the left entity.
+    /// * `right` - The primary key of the right entity.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either whether a link was removed, or an [`Error`].
+    fn unlink<'a>(
+        left: &<Self::Left as PageableEntity>::Pk,
+        right: &<Self::Right as PageableEntity>::Pk,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<bool, Error>>;
+
+    /// Replace the links of the left entity with links to the given right entities, within a
+    /// transaction. Only the links which are no longer present are removed, and only the missing
+    /// links are created.
+    ///
+    /// # Arguments
+    ///
+    /// * `left` - The primary key of the left entity.
+    /// * `rights` - The primary keys of all right entities to link.
+    /// * `executor` - The connection to run the queries on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either `()`, or an [`Error`].
+    fn set_links<'a>(
+        left: &<Self::Left as PageableEntity>::Pk,
+        rights: &[<Self::Right as PageableEntity>::Pk],
+        executor: impl Acquire<'a, Database = Postgres>,
+    ) -> impl Future<Output = Result<(), Error>>;
+
+    /// Fetch the right entities linked to the left entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `left` - The primary key of the left entity.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the linked entities in no particular order, or an [`Error`].
+    fn linked_right<'a>(
+        left: &<Self::Left as PageableEntity>::Pk,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Vec<Self::Right>, Error>>;
+
+    /// Fetch the left entities linked to the right entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `right` - The primary key of the right entity.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the linked entities in no particular order, or an [`Error`].
+    fn linked_left<'a>(
+        right: &<Self::Right as PageableEntity>::Pk,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Vec<Self::Left>, Error>>;
 }
 
 /// Trait for types that can be updated in the database.
@@ -171,15 +1020,86 @@ pub trait UpdatableEntity {
     ///
     /// # Returns
     ///
-    /// A future resolving to either the updated entity or a SQLx error.
+    /// A future resolving to either the updated entity or an [`Error`]. For entities with a
+    /// `version` field, [`Error::StaleEntity`] is returned when the version no longer matches.
     fn update<'a>(
         &self,
         executor: impl PgExecutor<'a>,
-    ) -> impl Future<Output = Result<Self::SourceEntity, sqlx::Error>>;
+    ) -> impl Future<Output = Result<Self::SourceEntity, Error>>;
+
+    /// Update the entity in the database, only if it matches the guard. This is intended for
+    /// compare-and-swap updates, like only changing the status of an entity when it still has an
+    /// expected status. The guard is either a [`Filter`] on the columns of the entity, or
+    /// [`RawSql`] with bound values.
+    ///
+    /// # Arguments
+    ///
+    /// * `guard` - The condition the entity has to match before it is updated.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the updated entity, `None` if it does not match the guard, or
+    /// an [`Error`]. `None` is also returned when the entity does not exist, is soft deleted, or
+    /// its `version` no longer matches.
+    fn update_if<'a>(
+        &self,
+        guard: impl Into<Filter<Self::SourceEntity>>,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Option<Self::SourceEntity>, Error>>;
+
+    /// Update the entity in the database, if it exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the updated entity, `None` if it does not exist or is soft
+    /// deleted, or an [`Error`]. For entities with a `version` field, [`Error::StaleEntity`] is
+    /// still returned when no row matches, as the entity existed when it was fetched.
+    fn try_update<'a>(
+        &self,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Option<Self::SourceEntity>, Error>> {
+        async move {
+            match self.update(executor).await {
+                Ok(entity) => Ok(Some(entity)),
+                Err(Error::NotFound) => Ok(None),
+                Err(err) => Err(err),
+            }
+        }
+    }
+}
+
+/// Trait for types that can partially update an entity in the database.
+/// A "Patch" struct is generated for each entity, containing the primary key(s) and an optional
+/// value for each updatable field.
+pub trait PatchableEntity {
+    /// The entity type returned after updating (typically the main entity struct).
+    type SourceEntity;
+
+    /// Update only the fields of the entity which are present in the patch. Without any present
+    /// fields, the entity is returned as-is, without touching its `updated_at` or version fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the updated entity or an [`Error`]. For entities with a
+    /// `version` field, [`Error::StaleEntity`] is returned when the version no longer matches.
+    fn patch<'a>(
+        &self,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Self::SourceEntity, Error>>;
 }
 
 /// Trait for types that can be deleted from the database.
 /// This trait is implemented for the entity struct, allowing you to delete a row by its primary key(s).
+/// Entities without primary keys do not implement this trait.
 pub trait DeletableEntity {
     /// Delete the entity from the database by its primary key(s).
     ///
@@ -189,9 +1109,166 @@ pub trait DeletableEntity {
     ///
     /// # Returns
     ///
-    /// A future resolving to `()` if successful, or a SQLx error.
-    fn delete<'a>(
+    /// A future resolving to `()` if successful, or an [`Error`].
+    fn delete<'a>(&self, executor: impl PgExecutor<'a>) -> impl Future<Output = Result<(), Error>> {
+        async move { self.try_delete(executor).await.map(|_| ()) }
+    }
+
+    /// Delete the entity from the database by its primary key(s), reporting whether it existed.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either whether the entity was deleted, or an [`Error`]. Entities
+    /// which do not exist or are already soft deleted are not deleted.
+    fn try_delete<'a>(
         &self,
         executor: impl PgExecutor<'a>,
-    ) -> impl Future<Output = Result<(), sqlx::Error>>;
+    ) -> impl Future<Output = Result<bool, Error>>;
+
+    /// Delete the entity from the database by its primary key(s), returning the deleted row.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the deleted entity, `None` if it does not exist or is already
+    /// soft deleted, or an [`Error`]. Soft deleted entities are returned with their soft delete
+    /// column set.
+    fn delete_returning<'a>(
+        &self,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Option<Self>, Error>>
+    where
+        Self: Sized;
+}
+
+/// Trait for entities using soft delete, which can be restored or deleted permanently.
+/// This trait is implemented for the entity struct, for entities with primary key(s).
+pub trait SoftDeletableEntity: PageableEntity {
+    /// Restore the soft deleted entity, by resetting its soft delete column.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the restored entity, or an [`Error`]. When the entity does
+    /// not exist or is not soft deleted, [`Error::NotFound`] is returned.
+    fn restore<'a>(
+        &self,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Self, Error>>;
+
+    /// Delete the entity from the database by its primary key(s), instead of soft deleting it.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to `()` if successful, or an [`Error`].
+    fn force_delete<'a>(
+        &self,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<(), Error>>;
+
+    /// Delete all soft deleted entities from the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the amount of deleted entities, or an [`Error`].
+    fn purge_deleted<'a>(executor: impl PgExecutor<'a>)
+    -> impl Future<Output = Result<u64, Error>>;
+
+    /// Fetch the entity by its primary key(s), even when it is soft deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `pk` - The primary key(s) of the entity.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the entity if found, `None` if not found, or an [`Error`].
+    fn fetch_with_deleted<'a>(
+        pk: &Self::Pk,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<Option<Self>, Error>>
+    where
+        for<'p> Filter<Self>: From<&'p Self::Pk>,
+    {
+        let query = Self::query().with_deleted().filter(pk);
+
+        async move { query.fetch_optional(executor).await }
+    }
+
+    /// Stream all entities from the database table, including the soft deleted ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// An async stream of results, each being either the entity or an [`Error`].
+    fn stream_with_deleted<'a>(
+        executor: impl PgExecutor<'a> + 'a,
+    ) -> impl Stream<Item = Result<Self, Error>>
+    where
+        Self: 'a,
+    {
+        Self::query().with_deleted().into_stream(executor)
+    }
+
+    /// Stream only the soft deleted entities from the database table.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// An async stream of results, each being either the entity or an [`Error`].
+    fn stream_only_deleted<'a>(
+        executor: impl PgExecutor<'a> + 'a,
+    ) -> impl Stream<Item = Result<Self, Error>>
+    where
+        Self: 'a,
+    {
+        Self::query().only_deleted().into_stream(executor)
+    }
+}
+
+/// Trait for entities soft deleted using a timestamp, which can be deleted permanently once they
+/// have been soft deleted for some time. This trait is implemented for the entity struct.
+pub trait PurgeableEntity: SoftDeletableEntity {
+    /// The type of the soft delete column (typically `chrono::NaiveDateTime`).
+    type DeletedAt;
+
+    /// Delete all entities which were soft deleted before the given timestamp from the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - The timestamp before which the entities were soft deleted.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the amount of deleted entities, or an [`Error`].
+    fn purge_deleted_before<'a>(
+        before: &Self::DeletedAt,
+        executor: impl PgExecutor<'a>,
+    ) -> impl Future<Output = Result<u64, Error>>;
 }
@@ -0,0 +1,1232 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    ops::Not,
+    sync::Arc,
+};
+
+use futures::{Stream, StreamExt, TryStreamExt};
+use sqlx::{
+    Arguments, Decode, Encode, PgExecutor, Postgres, Type,
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgArguments, PgHasArrayType, PgRow},
+};
+
+use crate::{Error, PageableEntity, QueryableEntity, Related};
+
+/// A value which can be bound to a query built at runtime
+pub trait Bindable: for<'q> Encode<'q, Postgres> + Type<Postgres> + Send + Sync + 'static {}
+
+impl<T> Bindable for T where T: for<'q> Encode<'q, Postgres> + Type<Postgres> + Send + Sync + 'static
+{}
+
+/// A type which can be summed using [`Query::sum`]
+pub trait Summable {
+    /// The type of the sum, which is wider than the summed type for integers
+    type Sum: for<'r> Decode<'r, Postgres> + Type<Postgres> + Send + Unpin;
+
+    /// The SQL type of the sum
+    #[doc(hidden)]
+    const SQL_TYPE: &'static str;
+}
+
+macro_rules! impl_summable {
+    ($($ty:ty => $sum:ty, $sql_type:literal);* $(;)?) => {
+        $(
+            impl Summable for $ty {
+                type Sum = $sum;
+
+                const SQL_TYPE: &'static str = $sql_type;
+            }
+        )*
+    };
+}
+
+impl_summable! {
+    i16 => i64, "int8";
+    i32 => i64, "int8";
+    i64 => i64, "int8";
+    f32 => f32, "float4";
+    f64 => f64, "float8";
+}
+
+impl<T: Summable> Summable for Option<T> {
+    type Sum = T::Sum;
+
+    const SQL_TYPE: &'static str = T::SQL_TYPE;
+}
+
+/// A bound value, which is encoded when the query is executed
+trait Argument: Send + Sync {
+    fn add_to(&self, arguments: &mut PgArguments) -> Result<(), BoxDynError>;
+}
+
+impl<T: Bindable> Argument for T {
+    fn add_to(&self, arguments: &mut PgArguments) -> Result<(), BoxDynError> {
+        arguments.add(self)
+    }
+}
+
+/// Part of a query, which is either SQL, a bound value or raw SQL with its own bound values
+#[derive(Clone)]
+enum Fragment {
+    Sql(Cow<'static, str>),
+    Argument(Arc<dyn Argument>),
+    Raw(RawSql),
+}
+
+/// Raw SQL with bound values, which can be used as a [`Filter`] for conditions which can not be
+/// expressed using the typed columns.
+///
+/// The values are referred to by the placeholders `$1`, `$2`, ..., in the order they are bound.
+/// The placeholders are renumbered when the filter is combined with other filters, except within
+/// quoted strings (including `E'...'` escape strings), quoted identifiers and comments.
+///
+/// ```rust,ignore
+/// let filter = RawSql::new("status = $1 AND attempts < $2").bind("pending").bind(3);
+/// ```
+#[derive(Clone)]
+pub struct RawSql {
+    sql: Cow<'static, str>,
+    arguments: Vec<Arc<dyn Argument>>,
+}
+
+impl RawSql {
+    /// Start from the SQL, without any bound values
+    pub fn new(sql: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            sql: sql.into(),
+            arguments: vec![],
+        }
+    }
+
+    /// Bind the value to the next placeholder
+    pub fn bind(mut self, value: impl Bindable) -> Self {
+        self.arguments.push(Arc::new(value));
+        self
+    }
+
+    /// Render the SQL, renumbering the placeholders after the already added arguments
+    fn render(&self, sql: &mut String, arguments: &mut PgArguments) -> Result<(), BoxDynError> {
+        let offset = arguments.len();
+
+        for value in &self.arguments {
+            value.add_to(arguments)?;
+        }
+
+        let mut rest = self.sql.as_ref();
+
+        while let Some(idx) = rest.find(['$', '\'', '"', '-', '/', 'E', 'e']) {
+            let (before, from) = rest.split_at(idx);
+            sql.push_str(before);
+
+            // Quoted strings, identifiers and comments are copied as-is, so they keep any `$n`
+            let skipped = match from.as_bytes() {
+                [b'\'', ..] => quoted(from, 1, "'"),
+                // An `E` prefix is only an escape string when it does not end an identifier
+                [b'E' | b'e', b'\'', ..]
+                    if !sql.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == '$') =>
+                {
+                    escape_quoted(from)
+                }
+                [b'"', ..] => quoted(from, 1, "\""),
+                [b'-', b'-', ..] => quoted(from, 2, "\n"),
+                [b'/', b'*', ..] => quoted(from, 2, "*/"),
+                [b'$', next, ..] if next.is_ascii_digit() => None,
+                [b'$', ..] => dollar_quoted(from),
+                _ => Some(1),
+            };
+
+            if let Some(len) = skipped {
+                sql.push_str(&from[..len]);
+                rest = &from[len..];
+                continue;
+            }
+
+            let digits = from[1..]
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(from.len(), |len| len + 1);
+
+            let placeholder = from[1..digits].parse::<usize>()?;
+
+            if placeholder == 0 || placeholder > self.arguments.len() {
+                return Err(format!("No value is bound to the placeholder ${placeholder}").into());
+            }
+
+            sql.push_str(&format!("${}", offset + placeholder));
+            rest = &from[digits..];
+        }
+
+        sql.push_str(rest);
+
+        Ok(())
+    }
+}
+
+/// The length of the region opened by the first `open` bytes of the SQL and closed by the
+/// delimiter, or the rest of the SQL when it is not closed
+fn quoted(sql: &str, open: usize, delimiter: &str) -> Option<usize> {
+    Some(
+        sql[open..]
+            .find(delimiter)
+            .map_or(sql.len(), |idx| open + idx + delimiter.len()),
+    )
+}
+
+/// The length of the escape string like `E'...'` at the start of the SQL, in which a backslash
+/// escapes the next character, or the rest of the SQL when it is not closed
+fn escape_quoted(sql: &str) -> Option<usize> {
+    let bytes = sql.as_bytes();
+    let mut idx = 2;
+
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' => idx += 2,
+            b'\'' => return Some(idx + 1),
+            _ => idx += 1,
+        }
+    }
+
+    Some(sql.len())
+}
+
+/// The length of the dollar quoted string like `$$...$$` or `$tag$...$tag$` at the start of the
+/// SQL, or of the single `$` when it does not start a dollar quoted string
+fn dollar_quoted(sql: &str) -> Option<usize> {
+    let tag = sql[1..]
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|&len| sql[1 + len..].starts_with('$'))
+        .map(|len| &sql[..len + 2]);
+
+    let Some(tag) = tag else {
+        return Some(1);
+    };
+
+    Some(
+        sql[tag.len()..]
+            .find(tag)
+            .map_or(sql.len(), |idx| 2 * tag.len() + idx),
+    )
+}
+
+/// SQL with bound values, rendered with positional placeholders once the query is executed
+#[derive(Clone, Default)]
+pub(crate) struct Sql {
+    fragments: Vec<Fragment>,
+}
+
+impl Sql {
+    pub(crate) fn new(sql: impl Into<Cow<'static, str>>) -> Self {
+        Self::default().push(sql)
+    }
+
+    pub(crate) fn push(mut self, sql: impl Into<Cow<'static, str>>) -> Self {
+        self.fragments.push(Fragment::Sql(sql.into()));
+        self
+    }
+
+    pub(crate) fn bind(mut self, value: impl Bindable) -> Self {
+        self.fragments.push(Fragment::Argument(Arc::new(value)));
+        self
+    }
+
+    pub(crate) fn append(mut self, other: Self) -> Self {
+        self.fragments.extend(other.fragments);
+        self
+    }
+
+    /// Render the SQL, adding the bound values to the arguments
+    pub(crate) fn render(
+        &self,
+        sql: &mut String,
+        arguments: &mut PgArguments,
+    ) -> Result<(), BoxDynError> {
+        for fragment in &self.fragments {
+            match fragment {
+                Fragment::Sql(value) => sql.push_str(value),
+                Fragment::Argument(value) => {
+                    value.add_to(arguments)?;
+                    arguments.format_placeholder(sql)?;
+                }
+                Fragment::Raw(raw) => raw.render(sql, arguments)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Render the SQL into a query, with the arguments to execute it with
+    pub(crate) fn build(&self) -> Result<(String, PgArguments), Error> {
+        let mut sql = String::new();
+        let mut arguments = PgArguments::default();
+
+        self.render(&mut sql, &mut arguments)
+            .map_err(|err| Error::Database(sqlx::Error::Encode(err)))?;
+
+        Ok((sql, arguments))
+    }
+}
+
+/// Whether the value is encoded as `NULL`, which never compares equal to anything
+fn is_null(value: &impl Bindable) -> bool {
+    matches!(
+        value.encode_by_ref(&mut PgArgumentBuffer::default()),
+        Ok(IsNull::Yes)
+    )
+}
+
+/// A column of an entity, which is used to build filters and orderings.
+///
+/// The columns of an entity are available through [`QueryableEntity::columns`].
+pub struct Column<E, T> {
+    name: &'static str,
+    _marker: PhantomData<fn() -> (E, T)>,
+}
+
+impl<E, T> Clone for Column<E, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E, T> Copy for Column<E, T> {}
+
+impl<E, T> std::fmt::Debug for Column<E, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Column").field(&self.name).finish()
+    }
+}
+
+impl<E, T> Column<E, T> {
+    #[doc(hidden)]
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The name of the column, as it is used in queries
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn compare(self, operator: &'static str, value: impl Bindable) -> Filter<E> {
+        Filter::new(Sql::new(self.name).push(operator).bind(value))
+    }
+
+    /// Filter for rows where the column is equal to the value. A `NULL` value, like `None`,
+    /// filters for rows where the column is `NULL`, like [`Column::is_null`].
+    pub fn eq(self, value: impl Into<T>) -> Filter<E>
+    where
+        T: Bindable,
+    {
+        let value = value.into();
+
+        if is_null(&value) {
+            return self.is_null();
+        }
+
+        self.compare(" = ", value)
+    }
+
+    /// Filter for rows where the column is not equal to the value. A `NULL` value, like `None`,
+    /// filters for rows where the column is not `NULL`, like [`Column::is_not_null`].
+    pub fn ne(self, value: impl Into<T>) -> Filter<E>
+    where
+        T: Bindable,
+    {
+        let value = value.into();
+
+        if is_null(&value) {
+            return self.is_not_null();
+        }
+
+        self.compare(" <> ", value)
+    }
+
+    /// Filter for rows where the column is less than the value
+    pub fn lt(self, value: impl Into<T>) -> Filter<E>
+    where
+        T: Bindable,
+    {
+        self.compare(" < ", value.into())
+    }
+
+    /// Filter for rows where the column is less than or equal to the value
+    pub fn le(self, value: impl Into<T>) -> Filter<E>
+    where
+        T: Bindable,
+    {
+        self.compare(" <= ", value.into())
+    }
+
+    /// Filter for rows where the column is greater than the value
+    pub fn gt(self, value: impl Into<T>) -> Filter<E>
+    where
+        T: Bindable,
+    {
+        self.compare(" > ", value.into())
+    }
+
+    /// Filter for rows where the column is greater than or equal to the value
+    pub fn ge(self, value: impl Into<T>) -> Filter<E>
+    where
+        T: Bindable,
+    {
+        self.compare(" >= ", value.into())
+    }
+
+    /// Filter for rows where the column is equal to any of the values
+    pub fn is_in(self, values: impl IntoIterator<Item = impl Into<T>>) -> Filter<E>
+    where
+        T: Bindable + PgHasArrayType,
+    {
+        let values = values.into_iter().map(Into::into).collect::<Vec<T>>();
+
+        Filter::new(Sql::new(self.name).push(" = ANY(").bind(values).push(")"))
+    }
+
+    /// Filter for rows where the column is equal to any value of a column selected by another
+    /// query, like `a IN (SELECT b FROM ...)`
+    pub fn in_query<S: QueryableEntity, U>(
+        self,
+        query: &Query<S>,
+        column: Column<S, U>,
+    ) -> Filter<E> {
+        Filter::new(
+            Sql::new(self.name)
+                .push(" IN (")
+                .append(query.select(column.name))
+                .push(")"),
+        )
+    }
+
+    /// Filter for rows where the column matches the pattern using `LIKE`
+    pub fn like(self, pattern: impl Into<String>) -> Filter<E> {
+        self.compare(" LIKE ", pattern.into())
+    }
+
+    /// Filter for rows where the column matches the pattern using `ILIKE`, ignoring case
+    pub fn ilike(self, pattern: impl Into<String>) -> Filter<E> {
+        self.compare(" ILIKE ", pattern.into())
+    }
+
+    /// Filter for rows where the column is `NULL`
+    pub fn is_null(self) -> Filter<E> {
+        Filter::new(Sql::new(self.name).push(" IS NULL"))
+    }
+
+    /// Filter for rows where the column is not `NULL`
+    pub fn is_not_null(self) -> Filter<E> {
+        Filter::new(Sql::new(self.name).push(" IS NOT NULL"))
+    }
+
+    /// Order by the column in ascending order
+    pub fn asc(self) -> Order<E> {
+        Order::new(self.name, "ASC")
+    }
+
+    /// Order by the column in descending order
+    pub fn desc(self) -> Order<E> {
+        Order::new(self.name, "DESC")
+    }
+}
+
+/// A condition on the rows of an entity, built from its columns.
+///
+/// Filters can be combined using [`Filter::and`], [`Filter::or`] and negated using `!`.
+pub struct Filter<E> {
+    sql: Sql,
+    _marker: PhantomData<fn() -> E>,
+}
+
+impl<E> Clone for Filter<E> {
+    fn clone(&self) -> Self {
+        Self::new(self.sql.clone())
+    }
+}
+
+impl<E> Filter<E> {
+    pub(crate) fn new(sql: Sql) -> Self {
+        Self {
+            sql,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn sql(&self) -> &Sql {
+        &self.sql
+    }
+
+    /// Render the filter, adding the bound values after the already added arguments
+    #[doc(hidden)]
+    pub fn render(&self, sql: &mut String, arguments: &mut PgArguments) -> Result<(), Error> {
+        self.sql
+            .render(sql, arguments)
+            .map_err(|err| Error::Database(sqlx::Error::Encode(err)))
+    }
+
+    fn combine(self, operator: &'static str, other: Self) -> Self {
+        Self::new(
+            Sql::new("(")
+                .append(self.sql)
+                .push(operator)
+                .append(other.sql)
+                .push(")"),
+        )
+    }
+
+    /// Filter for rows matching both filters
+    pub fn and(self, other: Self) -> Self {
+        self.combine(" AND ", other)
+    }
+
+    /// Filter for rows matching either filter
+    pub fn or(self, other: Self) -> Self {
+        self.combine(" OR ", other)
+    }
+}
+
+impl<E> From<RawSql> for Filter<E> {
+    fn from(value: RawSql) -> Self {
+        Self::new(Sql {
+            fragments: vec![Fragment::Raw(value)],
+        })
+    }
+}
+
+impl<E> Not for Filter<E> {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self::new(Sql::new("NOT (").append(self.sql).push(")"))
+    }
+}
+
+/// An ordering of the rows of an entity, created using [`Column::asc`] or [`Column::desc`]
+pub struct Order<E> {
+    column: &'static str,
+    direction: &'static str,
+    _marker: PhantomData<fn() -> E>,
+}
+
+impl<E> Clone for Order<E> {
+    fn clone(&self) -> Self {
+        Self::new(self.column, self.direction)
+    }
+}
+
+impl<E> Order<E> {
+    fn new(column: &'static str, direction: &'static str) -> Self {
+        Self {
+            column,
+            direction,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<E, T> From<Column<E, T>> for Order<E> {
+    fn from(value: Column<E, T>) -> Self {
+        value.asc()
+    }
+}
+
+/// Multiple columns of an entity compared at once, like `(a, b) > ($1, $2)`.
+///
+/// Rows are compared column by column, which is used for keyset pagination over composite keys.
+pub struct RowValue<E> {
+    columns: Vec<&'static str>,
+    values: Vec<Sql>,
+    _marker: PhantomData<fn() -> E>,
+}
+
+impl<E> Default for RowValue<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> RowValue<E> {
+    /// Start a row value without any columns
+    pub fn new() -> Self {
+        Self {
+            columns: vec![],
+            values: vec![],
+            _marker: PhantomData,
+        }
+    }
+
+    /// Add a column with the value it is compared to
+    pub fn push<T: Bindable>(mut self, column: Column<E, T>, value: impl Into<T>) -> Self {
+        self.columns.push(column.name);
+        self.values.push(Sql::default().bind(value.into()));
+        self
+    }
+
+    fn compare(self, operator: &'static str) -> Filter<E> {
+        let mut sql = Sql::new(format!("({}){operator}(", self.columns.join(", ")));
+
+        for (idx, value) in self.values.into_iter().enumerate() {
+            if idx > 0 {
+                sql = sql.push(", ");
+            }
+
+            sql = sql.append(value);
+        }
+
+        Filter::new(sql.push(")"))
+    }
+
+    /// Filter for rows matching the values
+    pub fn eq(self) -> Filter<E> {
+        self.compare(" = ")
+    }
+
+    /// Filter for rows which come after the values
+    pub fn gt(self) -> Filter<E> {
+        self.compare(" > ")
+    }
+
+    /// Filter for rows which come before the values
+    pub fn lt(self) -> Filter<E> {
+        self.compare(" < ")
+    }
+}
+
+/// Multiple columns of an entity matched against lists of values at once, like
+/// `(a, b) IN (SELECT * FROM UNNEST($1, $2))`.
+///
+/// The lists are zipped, so each row of values has to match as a whole. For a single column,
+/// [`Column::is_in`] can be used instead.
+pub struct RowValues<E> {
+    columns: Vec<&'static str>,
+    values: Vec<Sql>,
+    _marker: PhantomData<fn() -> E>,
+}
+
+impl<E> Default for RowValues<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> RowValues<E> {
+    /// Start without any columns
+    pub fn new() -> Self {
+        Self {
+            columns: vec![],
+            values: vec![],
+            _marker: PhantomData,
+        }
+    }
+
+    /// Add a column with the list of values it is matched against
+    pub fn push<T>(
+        mut self,
+        column: Column<E, T>,
+        values: impl IntoIterator<Item = impl Into<T>>,
+    ) -> Self
+    where
+        T: Bindable + PgHasArrayType,
+    {
+        let values = values.into_iter().map(Into::into).collect::<Vec<T>>();
+
+        self.columns.push(column.name);
+        self.values.push(Sql::default().bind(values));
+        self
+    }
+
+    /// Filter for rows matching any of the rows of values
+    pub fn is_in(self) -> Filter<E> {
+        let mut sql = Sql::new(format!(
+            "({}) IN (SELECT * FROM UNNEST(",
+            self.columns.join(", ")
+        ));
+
+        for (idx, value) in self.values.into_iter().enumerate() {
+            if idx > 0 {
+                sql = sql.push(", ");
+            }
+
+            sql = sql.append(value);
+        }
+
+        Filter::new(sql.push("))"))
+    }
+}
+
+/// A row lock taken by a query, like `FOR UPDATE SKIP LOCKED`, which is used with
+/// [`Query::lock`].
+///
+/// Locks are held until the end of the transaction, so they should be taken within a
+/// transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lock {
+    strength: &'static str,
+    wait: &'static str,
+}
+
+impl Lock {
+    /// Lock the rows for updating or deleting them (`FOR UPDATE`)
+    pub const fn update() -> Self {
+        Self {
+            strength: " FOR UPDATE",
+            wait: "",
+        }
+    }
+
+    /// Lock the rows for updating them, without blocking locks of referencing foreign keys
+    /// (`FOR NO KEY UPDATE`)
+    pub const fn no_key_update() -> Self {
+        Self {
+            strength: " FOR NO KEY UPDATE",
+            wait: "",
+        }
+    }
+
+    /// Lock the rows against updates, while allowing other shared locks (`FOR SHARE`)
+    pub const fn share() -> Self {
+        Self {
+            strength: " FOR SHARE",
+            wait: "",
+        }
+    }
+
+    /// Fail instead of waiting when a row is locked by another transaction (`NOWAIT`)
+    pub const fn nowait(mut self) -> Self {
+        self.wait = " NOWAIT";
+        self
+    }
+
+    /// Skip the rows which are locked by another transaction, instead of waiting for them
+    /// (`SKIP LOCKED`)
+    pub const fn skip_locked(mut self) -> Self {
+        self.wait = " SKIP LOCKED";
+        self
+    }
+}
+
+/// A page of entities, returned by [`Query::page_after`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<E, K> {
+    /// The entities on the page, ordered by their primary key(s)
+    pub items: Vec<E>,
+    /// The cursor to fetch the next page with, or `None` if this is the last page
+    pub next: Option<K>,
+}
+
+/// Which rows of a soft delete entity are selected by a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Deleted {
+    Exclude,
+    Include,
+    Only,
+}
+
+/// A query selecting entities, created using [`QueryableEntity::query`].
+///
+/// The query honours soft delete, so only rows which are not soft deleted are returned, unless
+/// [`Query::with_deleted`] or [`Query::only_deleted`] is used.
+pub struct Query<E> {
+    filters: Vec<Filter<E>>,
+    order: Vec<Order<E>>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    deleted: Deleted,
+    lock: Option<Lock>,
+}
+
+impl<E> Clone for Query<E> {
+    fn clone(&self) -> Self {
+        Self {
+            filters: self.filters.clone(),
+            order: self.order.clone(),
+            limit: self.limit,
+            offset: self.offset,
+            deleted: self.deleted,
+            lock: self.lock,
+        }
+    }
+}
+
+impl<E: QueryableEntity> Default for Query<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: QueryableEntity> Query<E> {
+    pub(crate) fn new() -> Self {
+        Self {
+            filters: vec![],
+            order: vec![],
+            limit: None,
+            offset: None,
+            deleted: Deleted::Exclude,
+            lock: None,
+        }
+    }
+
+    /// Only select rows matching the filter. Multiple filters are combined using `AND`.
+    pub fn filter(mut self, filter: impl Into<Filter<E>>) -> Self {
+        self.filters.push(filter.into());
+        self
+    }
+
+    /// Order the rows by the given column, after any previous orderings
+    pub fn order_by(mut self, order: impl Into<Order<E>>) -> Self {
+        self.order.push(order.into());
+        self
+    }
+
+    /// Select at most the given amount of rows. Limits above `i64::MAX`, the largest limit
+    /// PostgreSQL supports, are clamped.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit.min(i64::MAX as u64));
+        self
+    }
+
+    /// Skip the given amount of rows. Offsets above `i64::MAX`, the largest offset PostgreSQL
+    /// supports, are clamped.
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset.min(i64::MAX as u64));
+        self
+    }
+
+    /// Also select rows which are soft deleted. Has no effect for entities without soft delete.
+    pub fn with_deleted(mut self) -> Self {
+        self.deleted = Deleted::Include;
+        self
+    }
+
+    /// Only select rows which are soft deleted. Selects no rows for entities without soft delete.
+    pub fn only_deleted(mut self) -> Self {
+        self.deleted = Deleted::Only;
+        self
+    }
+
+    /// Lock the selected rows, like `FOR UPDATE`, until the end of the transaction
+    pub fn lock(mut self, lock: Lock) -> Self {
+        self.lock = Some(lock);
+        self
+    }
+
+    /// The conditions of the query, including the soft delete predicate
+    pub(crate) fn where_clause(&self) -> Sql {
+        let soft_delete = match (self.deleted, E::SOFT_DELETE) {
+            (Deleted::Exclude, predicate) => predicate.map(Sql::new),
+            (Deleted::Include, _) => None,
+            (Deleted::Only, Some(predicate)) => {
+                Some(Sql::new("(").push(predicate).push(") IS NOT TRUE"))
+            }
+            (Deleted::Only, None) => Some(Sql::new("FALSE")),
+        };
+
+        let conditions = soft_delete
+            .into_iter()
+            .chain(self.filters.iter().map(|filter| filter.sql().clone()));
+
+        let mut sql = Sql::default();
+
+        for (idx, condition) in conditions.enumerate() {
+            sql = sql
+                .push(if idx == 0 { " WHERE (" } else { " AND (" })
+                .append(condition)
+                .push(")");
+        }
+
+        sql
+    }
+
+    /// Build the query, selecting the given columns
+    pub(crate) fn select(&self, columns: &str) -> Sql {
+        let mut sql =
+            Sql::new(format!("SELECT {columns} FROM {}", E::TABLE)).append(self.where_clause());
+
+        for (idx, order) in self.order.iter().enumerate() {
+            sql = sql
+                .push(if idx == 0 { " ORDER BY " } else { ", " })
+                .push(order.column)
+                .push(" ")
+                .push(order.direction);
+        }
+
+        if let Some(limit) = self.limit {
+            sql = sql.push(format!(" LIMIT {limit}"));
+        }
+
+        if let Some(offset) = self.offset {
+            sql = sql.push(format!(" OFFSET {offset}"));
+        }
+
+        if let Some(lock) = self.lock {
+            sql = sql.push(lock.strength).push(lock.wait);
+        }
+
+        sql
+    }
+
+    fn query(&self) -> Result<sqlx::query::Query<'static, Postgres, PgArguments>, Error> {
+        let (sql, arguments) = self.select(E::COLUMNS).build()?;
+
+        Ok(sqlx::query_with(sqlx::AssertSqlSafe(sql), arguments))
+    }
+
+    /// Select a single value computed over the rows matching the query
+    async fn aggregate<'a, T>(
+        &self,
+        expression: String,
+        executor: impl PgExecutor<'a>,
+    ) -> Result<T, Error>
+    where
+        T: for<'r> Decode<'r, Postgres> + Type<Postgres> + Send + Unpin,
+    {
+        let (sql, arguments) = Sql::new(format!("SELECT {expression} FROM ("))
+            .append(self.select("*"))
+            .push(") AS query")
+            .build()?;
+
+        Ok(sqlx::query_scalar_with(sqlx::AssertSqlSafe(sql), arguments)
+            .fetch_one(executor)
+            .await?)
+    }
+
+    /// Count the entities matching the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the amount of entities, or an [`Error`].
+    pub async fn count<'a>(&self, executor: impl PgExecutor<'a>) -> Result<i64, Error> {
+        self.aggregate("COUNT(*)".to_string(), executor).await
+    }
+
+    /// Check whether any entity matches the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either whether an entity exists, or an [`Error`].
+    pub async fn exists<'a>(&self, executor: impl PgExecutor<'a>) -> Result<bool, Error> {
+        let (sql, arguments) = Sql::new("SELECT EXISTS (")
+            .append(self.select("1"))
+            .push(")")
+            .build()?;
+
+        Ok(sqlx::query_scalar_with(sqlx::AssertSqlSafe(sql), arguments)
+            .fetch_one(executor)
+            .await?)
+    }
+
+    /// The smallest value of the column among the entities matching the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - The column to find the smallest value of.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the smallest value, `None` if no entity matches, or an
+    /// [`Error`].
+    pub async fn min<'a, T>(
+        &self,
+        column: Column<E, T>,
+        executor: impl PgExecutor<'a>,
+    ) -> Result<Option<T>, Error>
+    where
+        T: for<'r> Decode<'r, Postgres> + Type<Postgres> + Send + Unpin,
+    {
+        self.aggregate(format!("MIN({})", column.name()), executor)
+            .await
+    }
+
+    /// The largest value of the column among the entities matching the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - The column to find the largest value of.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the largest value, `None` if no entity matches, or an
+    /// [`Error`].
+    pub async fn max<'a, T>(
+        &self,
+        column: Column<E, T>,
+        executor: impl PgExecutor<'a>,
+    ) -> Result<Option<T>, Error>
+    where
+        T: for<'r> Decode<'r, Postgres> + Type<Postgres> + Send + Unpin,
+    {
+        self.aggregate(format!("MAX({})", column.name()), executor)
+            .await
+    }
+
+    /// The sum of the column over the entities matching the query, which is zero if no entity
+    /// matches. `NULL` values are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - The column to sum.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the sum, or an [`Error`].
+    pub async fn sum<'a, T: Summable>(
+        &self,
+        column: Column<E, T>,
+        executor: impl PgExecutor<'a>,
+    ) -> Result<T::Sum, Error> {
+        self.aggregate(
+            format!("COALESCE(SUM({}), 0)::{}", column.name(), T::SQL_TYPE),
+            executor,
+        )
+        .await
+    }
+
+    /// Fetch the entities matching the query which are related to each of the given entities,
+    /// using a single query.
+    pub(crate) async fn fetch_related<'a, S>(
+        &self,
+        entities: &[S],
+        executor: impl PgExecutor<'a>,
+    ) -> Result<Vec<Vec<E>>, Error>
+    where
+        S: Related<E>,
+    {
+        let keys = entities.iter().map(S::key).collect::<Vec<_>>();
+
+        let mut seen = HashSet::new();
+        let unique = keys
+            .iter()
+            .flatten()
+            .filter(|key| seen.insert(*key))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if unique.is_empty() {
+            return Ok(entities.iter().map(|_| vec![]).collect());
+        }
+
+        // The key columns are selected after the columns of the entity, so each entity can be
+        // grouped by the key it was matched with
+        let columns = format!("{}, {}", E::COLUMNS, S::key_columns().join(", "));
+
+        let (sql, arguments) = self
+            .clone()
+            .filter(S::keys_filter(unique))
+            .select(&columns)
+            .build()?;
+
+        let rows = sqlx::query_with(sqlx::AssertSqlSafe(sql), arguments)
+            .fetch_all(executor)
+            .await?;
+
+        let mut related = HashMap::<S::Key, Vec<PgRow>>::new();
+
+        for row in rows {
+            related.entry(S::decode_key(&row)?).or_default().push(row);
+        }
+
+        // Entities sharing a key each decode their own copy of the related rows
+        let mut result = Vec::with_capacity(keys.len());
+
+        for key in &keys {
+            let rows = key
+                .as_ref()
+                .and_then(|key| related.get(key))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+
+            result.push(
+                rows.iter()
+                    .map(E::decode_row)
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+        }
+
+        Ok(result)
+    }
+
+    /// Fetch a page of entities matching the query, ordered by their primary key(s). Any ordering,
+    /// limit and offset of the query are replaced.
+    ///
+    /// Unlike offsets, the cursor refers to the last entity of the previous page, so pages stay
+    /// stable while entities are inserted concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor` - The cursor returned with the previous page, or `None` for the first page.
+    /// * `limit` - The maximum amount of entities on the page, which should be at least 1.
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the page, or an [`Error`]. [`Error::InvalidPageLimit`] is
+    /// returned when the limit is 0.
+    pub async fn page_after<'a>(
+        &self,
+        cursor: Option<E::Pk>,
+        limit: u64,
+        executor: impl PgExecutor<'a>,
+    ) -> Result<Page<E, E::Pk>, Error>
+    where
+        E: PageableEntity,
+    {
+        // An empty page can not tell whether more entities follow
+        if limit == 0 {
+            return Err(Error::InvalidPageLimit);
+        }
+
+        let mut query = self.clone();
+
+        if let Some(cursor) = &cursor {
+            query = query.filter(E::after(cursor));
+        }
+
+        // One more entity is fetched to tell whether there is a next page, within the range of
+        // the `BIGINT` limit of PostgreSQL
+        query.order = E::key_order();
+        query.limit = Some(limit.saturating_add(1).min(i64::MAX as u64));
+        query.offset = None;
+
+        let mut items = query.fetch_all(executor).await?;
+
+        let next = if items.len() as u64 > limit {
+            items.truncate(limit as usize);
+            items.last().map(E::Pk::from)
+        } else {
+            None
+        };
+
+        Ok(Page { items, next })
+    }
+
+    /// Claim the next entities matching the query, like taking jobs from a queue. The entities
+    /// are locked using `FOR UPDATE SKIP LOCKED`, so concurrent transactions claim different
+    /// entities, and remain locked until the end of the transaction.
+    ///
+    /// Entities are claimed in the order of the query, defaulting to their primary key(s).
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum amount of entities to claim, which should be at least 1.
+    /// * `executor` - The executor to run the query on, typically a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the claimed entities, or an [`Error`].
+    /// [`Error::InvalidPageLimit`] is returned when the limit is 0.
+    pub async fn claim_next<'a>(
+        &self,
+        limit: u64,
+        executor: impl PgExecutor<'a>,
+    ) -> Result<Vec<E>, Error>
+    where
+        E: PageableEntity,
+    {
+        if limit == 0 {
+            return Err(Error::InvalidPageLimit);
+        }
+
+        let mut query = self.clone();
+
+        if query.order.is_empty() {
+            query.order = E::key_order();
+        }
+
+        query
+            .limit(limit)
+            .lock(Lock::update().skip_locked())
+            .fetch_all(executor)
+            .await
+    }
+
+    /// Fetch all entities matching the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the entities, or an [`Error`].
+    pub async fn fetch_all<'a>(&self, executor: impl PgExecutor<'a>) -> Result<Vec<E>, Error> {
+        self.query()?
+            .fetch_all(executor)
+            .await?
+            .iter()
+            .map(|row| E::decode_row(row).map_err(Error::from))
+            .collect()
+    }
+
+    /// Fetch the first entity matching the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either `Some(entity)` if found, `None` if not found, or an [`Error`].
+    pub async fn fetch_optional<'a>(
+        &self,
+        executor: impl PgExecutor<'a>,
+    ) -> Result<Option<E>, Error> {
+        self.query()?
+            .fetch_optional(executor)
+            .await?
+            .map(|row| E::decode_row(&row).map_err(Error::from))
+            .transpose()
+    }
+
+    /// Stream all entities matching the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A stream of entities, or [`Error`]s.
+    pub fn stream<'a>(
+        &self,
+        executor: impl PgExecutor<'a> + 'a,
+    ) -> impl Stream<Item = Result<E, Error>> + 'a
+    where
+        E: 'a,
+    {
+        self.clone().into_stream(executor)
+    }
+
+    /// Stream all entities matching the query, without borrowing the query
+    pub(crate) fn into_stream<'a>(
+        self,
+        executor: impl PgExecutor<'a> + 'a,
+    ) -> impl Stream<Item = Result<E, Error>> + 'a
+    where
+        E: 'a,
+    {
+        match self.query() {
+            Ok(query) => query
+                .fetch(executor)
+                .map_err(Error::from)
+                .and_then(|row| async move { E::decode_row(&row).map_err(Error::from) })
+                .left_stream(),
+            Err(err) => futures::stream::once(async { Err(err) }).right_stream(),
+        }
+    }
+}
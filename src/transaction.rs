@@ -0,0 +1,201 @@
+use std::time::Duration;
+
+use sqlx::{Acquire, AssertSqlSafe, PgPool, Postgres, postgres::PgConnection};
+
+use crate::Error;
+
+/// The isolation level of a transaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IsolationLevel {
+    /// Only see data committed before each statement (`READ COMMITTED`)
+    #[default]
+    ReadCommitted,
+    /// Only see data committed before the transaction (`REPEATABLE READ`)
+    RepeatableRead,
+    /// Behave as if transactions were executed one after another (`SERIALIZABLE`)
+    Serializable,
+}
+
+impl IsolationLevel {
+    fn sql(self) -> &'static str {
+        match self {
+            Self::ReadCommitted => "READ COMMITTED",
+            Self::RepeatableRead => "REPEATABLE READ",
+            Self::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+/// The options of a transaction started using [`transaction`].
+///
+/// Transactions failing with a serialization failure or a deadlock are retried, waiting between
+/// the attempts with an exponential backoff. By default, a transaction is retried 3 times,
+/// starting with a backoff of 10 milliseconds, which doubles up to 1 second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionOptions {
+    isolation: IsolationLevel,
+    read_only: bool,
+    deferrable: bool,
+    retries: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for TransactionOptions {
+    fn default() -> Self {
+        Self::new(IsolationLevel::default())
+    }
+}
+
+impl From<IsolationLevel> for TransactionOptions {
+    fn from(value: IsolationLevel) -> Self {
+        Self::new(value)
+    }
+}
+
+impl TransactionOptions {
+    /// Start with the default options, using the given isolation level
+    pub fn new(isolation: IsolationLevel) -> Self {
+        Self {
+            isolation,
+            read_only: false,
+            deferrable: false,
+            retries: 3,
+            backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+
+    /// Only allow reading data within the transaction (`READ ONLY`)
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    /// Wait until the transaction can run without serialization failures (`DEFERRABLE`). Only
+    /// has an effect on `SERIALIZABLE` and `READ ONLY` transactions.
+    pub fn deferrable(mut self) -> Self {
+        self.deferrable = true;
+        self
+    }
+
+    /// Set the amount of times the transaction is retried, where `0` disables retrying
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set the backoff before the first retry, and the maximum backoff it doubles up to
+    pub fn backoff(mut self, backoff: Duration, max_backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    fn begin_sql(&self) -> String {
+        let mut sql = format!("BEGIN ISOLATION LEVEL {}", self.isolation.sql());
+
+        if self.read_only {
+            sql.push_str(" READ ONLY");
+        }
+
+        if self.deferrable {
+            sql.push_str(" DEFERRABLE");
+        }
+
+        sql
+    }
+}
+
+/// Run the closure within a transaction, which is committed when the closure succeeds and rolled
+/// back when it fails.
+///
+/// When the closure or the commit fails with a serialization failure or a deadlock, see
+/// [`Error::is_retryable`], the transaction is rolled back and the closure is run again, up to
+/// the amount of retries of the options. Use [`savepoint`] to nest transactions within the
+/// closure.
+///
+/// ```rust,ignore
+/// let artist = gremlin_orm::transaction(&pool, IsolationLevel::Serializable, async |conn| {
+///     let artist = InsertableArtist { name: "Testings".to_string() }
+///         .insert(&mut *conn)
+///         .await?;
+///
+///     Ok(artist)
+/// })
+/// .await?;
+/// ```
+///
+/// # Arguments
+///
+/// * `pool` - The pool to acquire the connection of the transaction from.
+/// * `options` - The isolation level, or the [`TransactionOptions`] of the transaction.
+/// * `f` - The closure to run, which receives the connection of the transaction.
+///
+/// # Returns
+///
+/// A future resolving to either the result of the closure, or an [`Error`].
+pub async fn transaction<T, F>(
+    pool: &PgPool,
+    options: impl Into<TransactionOptions>,
+    mut f: F,
+) -> Result<T, Error>
+where
+    F: AsyncFnMut(&mut PgConnection) -> Result<T, Error>,
+{
+    let options = options.into();
+    let begin = options.begin_sql();
+
+    let mut backoff = options.backoff;
+    let mut attempt = 0;
+
+    loop {
+        match run(pool, &begin, &mut f).await {
+            Err(err) if err.is_retryable() && attempt < options.retries => {
+                tokio::time::sleep(backoff).await;
+
+                backoff = (backoff * 2).min(options.max_backoff);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Run a single attempt of the transaction
+async fn run<T, F>(pool: &PgPool, begin: &str, f: &mut F) -> Result<T, Error>
+where
+    F: AsyncFnMut(&mut PgConnection) -> Result<T, Error>,
+{
+    let mut tx = pool.begin_with(AssertSqlSafe(begin.to_string())).await?;
+    let value = f(&mut tx).await?;
+    tx.commit().await?;
+
+    Ok(value)
+}
+
+/// Run the closure within a savepoint of the transaction, which is released when the closure
+/// succeeds and rolled back when it fails, leaving the rest of the transaction intact. Outside of
+/// a transaction, a new transaction is started instead.
+///
+/// # Arguments
+///
+/// * `executor` - The connection of the transaction, like the one received by [`transaction`].
+/// * `f` - The closure to run, which receives the connection of the transaction.
+///
+/// # Returns
+///
+/// A future resolving to either the result of the closure, or an [`Error`].
+pub async fn savepoint<'a, T, F>(
+    executor: impl Acquire<'a, Database = Postgres>,
+    f: F,
+) -> Result<T, Error>
+where
+    F: AsyncFnOnce(&mut PgConnection) -> Result<T, Error>,
+{
+    let mut tx = executor.begin().await?;
+    let value = f(&mut tx).await?;
+    tx.commit().await?;
+
+    Ok(value)
+}
@@ -0,0 +1,230 @@
+use sqlx::{PgExecutor, postgres::PgHasArrayType};
+
+use crate::{
+    Error, QueryableEntity,
+    query::{Bindable, Column, Filter, Query, Sql, Summable},
+};
+
+/// An array column, which values can be appended to using [`Update::append`]
+pub trait Appendable {
+    /// The type of the elements of the array
+    type Element: Bindable;
+}
+
+impl<T: Bindable + PgHasArrayType> Appendable for Vec<T> {
+    type Element = T;
+}
+
+impl<T: Appendable> Appendable for Option<T> {
+    type Element = T::Element;
+}
+
+/// An update of the entities matching a filter, created using [`QueryableEntity::update_where`].
+///
+/// The columns are changed using SQL expressions, so increments and appends are applied
+/// atomically by the database, instead of racing between a fetch and an update. The update
+/// honours soft delete, so soft deleted rows are left untouched.
+pub struct Update<E> {
+    query: Query<E>,
+    changes: Vec<(&'static str, Sql)>,
+}
+
+impl<E> Clone for Update<E> {
+    fn clone(&self) -> Self {
+        Self {
+            query: self.query.clone(),
+            changes: self.changes.clone(),
+        }
+    }
+}
+
+impl<E: QueryableEntity> Update<E> {
+    pub(crate) fn new(filter: Filter<E>) -> Self {
+        Self {
+            query: Query::new().filter(filter),
+            changes: vec![],
+        }
+    }
+
+    /// Only update rows which also match the filter. Multiple filters are combined using `AND`.
+    pub fn filter(mut self, filter: impl Into<Filter<E>>) -> Self {
+        self.query = self.query.filter(filter);
+        self
+    }
+
+    /// Set the column to the value, like `column = $1`. Setting an `updated_at` or version column
+    /// replaces its assignment made by every update, so the given value is stored as-is.
+    pub fn set<T: Bindable>(mut self, column: Column<E, T>, value: impl Into<T>) -> Self {
+        self.changes.push((
+            column.name(),
+            Sql::new(format!("{} = ", column.name())).bind(value.into()),
+        ));
+        self
+    }
+
+    /// Add the value to the column, like `column = column + $1`
+    pub fn increment<T>(mut self, column: Column<E, T>, value: impl Into<T>) -> Self
+    where
+        T: Bindable + Summable,
+    {
+        self.changes.push((
+            column.name(),
+            Sql::new(format!("{0} = {0} + ", column.name())).bind(value.into()),
+        ));
+        self
+    }
+
+    /// Subtract the value from the column, like `column = column - $1`
+    pub fn decrement<T>(mut self, column: Column<E, T>, value: impl Into<T>) -> Self
+    where
+        T: Bindable + Summable,
+    {
+        self.changes.push((
+            column.name(),
+            Sql::new(format!("{0} = {0} - ", column.name())).bind(value.into()),
+        ));
+        self
+    }
+
+    /// Append the value to the array column, like `column = array_append(column, $1)`. A `NULL`
+    /// array is treated like an empty one.
+    pub fn append<T: Appendable>(
+        mut self,
+        column: Column<E, T>,
+        value: impl Into<T::Element>,
+    ) -> Self {
+        self.changes.push((
+            column.name(),
+            Sql::new(format!("{0} = array_append({0}, ", column.name()))
+                .bind(value.into())
+                .push(")"),
+        ));
+        self
+    }
+
+    /// Build the statement, or `None` if there are no changes to make
+    fn statement(&self) -> Option<Sql> {
+        if self.changes.is_empty() {
+            return None;
+        }
+
+        let mut sql = Sql::new(format!("UPDATE {} SET ", E::TABLE));
+
+        for (idx, (_, change)) in self.changes.iter().enumerate() {
+            if idx > 0 {
+                sql = sql.push(", ");
+            }
+
+            sql = sql.append(change.clone());
+        }
+
+        // A column can only be assigned once, so the changes take precedence
+        for (column, value) in E::ON_UPDATE {
+            if !self.changes.iter().any(|(changed, _)| changed == column) {
+                sql = sql.push(format!(", {column} = {value}"));
+            }
+        }
+
+        Some(sql.append(self.query.where_clause()))
+    }
+
+    /// Build the statement returning the updated entities, or `None` if there are no changes
+    fn returning(&self) -> Option<Sql> {
+        self.statement()
+            .map(|sql| sql.push(format!(" RETURNING {}", E::COLUMNS)))
+    }
+
+    /// Update the matching entities. Without any changes, the matching entities are counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the amount of updated entities, or an [`Error`].
+    pub async fn execute<'a>(&self, executor: impl PgExecutor<'a>) -> Result<u64, Error> {
+        let Some(statement) = self.statement() else {
+            return Ok(self
+                .query
+                .count(executor)
+                .await?
+                .try_into()
+                .unwrap_or_default());
+        };
+
+        let (sql, arguments) = statement.build()?;
+
+        Ok(sqlx::query_with(sqlx::AssertSqlSafe(sql), arguments)
+            .execute(executor)
+            .await?
+            .rows_affected())
+    }
+
+    /// Update the matching entities, returning them as they are after the update. Without any
+    /// changes, the matching entities are returned as-is.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the updated entities, or an [`Error`].
+    pub async fn fetch_all<'a>(&self, executor: impl PgExecutor<'a>) -> Result<Vec<E>, Error> {
+        let Some(statement) = self.returning() else {
+            return self.query.fetch_all(executor).await;
+        };
+
+        let (sql, arguments) = statement.build()?;
+
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), arguments)
+            .fetch_all(executor)
+            .await?
+            .iter()
+            .map(|row| E::decode_row(row).map_err(Error::from))
+            .collect()
+    }
+
+    /// Update the matching entity, returning it as it is after the update. Intended for filters
+    /// matching at most one row, like a primary key.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either `Some(entity)` if updated, `None` if no entity matches, or an
+    /// [`Error`].
+    pub async fn fetch_optional<'a>(
+        &self,
+        executor: impl PgExecutor<'a>,
+    ) -> Result<Option<E>, Error> {
+        let Some(statement) = self.returning() else {
+            return self.query.fetch_optional(executor).await;
+        };
+
+        let (sql, arguments) = statement.build()?;
+
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), arguments)
+            .fetch_optional(executor)
+            .await?
+            .map(|row| E::decode_row(&row).map_err(Error::from))
+            .transpose()
+    }
+
+    /// Update the matching entity, like [`Update::fetch_optional`], but returning
+    /// [`Error::NotFound`] if no entity matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - The executor to run the query on, like a pool or a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A future resolving to either the updated entity, or an [`Error`].
+    pub async fn fetch_one<'a>(&self, executor: impl PgExecutor<'a>) -> Result<E, Error> {
+        self.fetch_optional(executor).await?.ok_or(Error::NotFound)
+    }
+}
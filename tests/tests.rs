@@ -2,7 +2,8 @@ use assert2::check;
 use chrono::NaiveDateTime;
 use futures::StreamExt;
 use gremlin_orm::{
-    DeletableEntity, Entity, FetchableEntity, InsertableEntity, StreamableEntity, UpdatableEntity,
+    CopyableEntity, DeletableEntity, Entity, FetchableEntity, InsertableEntity, StreamableEntity,
+    UpdatableEntity, UpsertableEntity,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::{
@@ -12,8 +13,8 @@ use sqlx::{
 };
 
 // Generic entity
-#[derive(Debug, Entity, PartialEq, Eq, FromRow)]
-#[orm(table = "public.artist")]
+#[derive(Debug, Clone, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.artist", has_many(ArtistRelease))]
 struct Artist {
     #[orm(pk, generated)]
     id: i32,
@@ -23,8 +24,12 @@ struct Artist {
 }
 
 // Deref fields
-#[derive(Debug, Entity, PartialEq, Eq, FromRow)]
-#[orm(table = "public.release")]
+#[derive(Debug, Clone, Entity, PartialEq, Eq, FromRow)]
+#[orm(
+    table = "public.release",
+    has_many(Track),
+    has_many(ArtistRelease, name = "artist_links")
+)]
 struct Release {
     #[orm(pk, generated)]
     id: i32,
@@ -53,12 +58,73 @@ struct GeneratedOnly {
 
 // Multiple pks
 #[derive(Debug, Entity, PartialEq, Eq, FromRow)]
-#[orm(table = "public.artist_release")]
+#[orm(
+    table = "public.artist_release",
+    belongs_to(Artist, fk = "artist_id"),
+    has_many(Credit, fk = "artist_id, release_id"),
+    join(left = Artist, right = Release)
+)]
 struct ArtistRelease {
     #[orm(pk)]
     artist_id: i32,
-    #[orm(pk)]
+    #[orm(pk, belongs_to = Release)]
+    release_id: i32,
+}
+
+// Composite foreign keys
+#[derive(Debug, Clone, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.credit")]
+struct Credit {
+    #[orm(pk, generated)]
+    id: i32,
+    artist_id: i32,
     release_id: i32,
+    role: String,
+}
+
+// Nullable foreign keys
+#[derive(Debug, Clone, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.track", belongs_to(Release))]
+struct Track {
+    #[orm(pk, generated)]
+    id: i32,
+    #[orm(as_ref)]
+    release_id: Option<i32>,
+    name: String,
+}
+
+// Optimistic locking
+#[derive(Debug, Clone, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.document")]
+struct Document {
+    #[orm(pk, generated)]
+    id: i32,
+    title: String,
+    #[orm(version, default)]
+    version: i32,
+}
+
+#[derive(Debug, Clone, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.setting")]
+struct Setting {
+    #[orm(pk)]
+    name: String,
+    value: String,
+    #[orm(version, default)]
+    version: i32,
+}
+
+// Timestamp fields
+#[derive(Debug, Clone, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.post")]
+struct Post {
+    #[orm(pk, generated)]
+    id: i32,
+    title: String,
+    #[orm(created_at)]
+    created_at: NaiveDateTime,
+    #[orm(updated_at)]
+    updated_at: NaiveDateTime,
 }
 
 // Defaultable fields
@@ -116,6 +182,163 @@ struct SoftDelete {
     deleted_at: Option<NaiveDateTime>,
 }
 
+// Soft delete using a boolean flag
+#[derive(Debug, Clone, Entity, PartialEq, Eq, FromRow)]
+#[orm(
+    table = "public.flag_soft_delete",
+    soft_delete(column = "is_deleted", kind = "bool")
+)]
+struct FlagSoftDelete {
+    #[orm(pk, generated)]
+    id: i32,
+    value: i32,
+    #[orm(default)]
+    is_deleted: bool,
+}
+
+// Soft delete using custom expressions
+#[derive(Debug, Clone, Entity, PartialEq, Eq, FromRow)]
+#[orm(
+    table = "public.status_soft_delete",
+    soft_delete(
+        column = "status",
+        set = "'deleted'",
+        filter = "status <> 'deleted'",
+        restore = "'active'"
+    ),
+    checked = false
+)]
+struct StatusSoftDelete {
+    #[orm(pk, generated)]
+    id: i32,
+    value: i32,
+    #[orm(default)]
+    status: String,
+}
+
+// Soft delete using custom expressions on a nullable column
+#[derive(Debug, Clone, Entity, PartialEq, Eq, FromRow)]
+#[orm(
+    table = "public.nullable_status_soft_delete",
+    soft_delete(
+        column = "status",
+        set = "'deleted'",
+        filter = "status <> 'deleted'",
+        restore = "'active'"
+    ),
+    checked = false
+)]
+struct NullableStatusSoftDelete {
+    #[orm(pk, generated)]
+    id: i32,
+    value: i32,
+    #[orm(deref, default)]
+    status: Option<String>,
+}
+
+// Renamed columns
+#[derive(Debug, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.legacy_account", rename_all = "camelCase")]
+struct LegacyAccount {
+    #[orm(pk, generated)]
+    account_id: i32,
+    display_name: String,
+    #[orm(deref, column = "EMAIL")]
+    email: Option<String>,
+}
+
+// Reserved words as identifiers
+#[derive(Debug, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = r#"public."Order""#)]
+struct Order {
+    #[orm(pk, generated)]
+    id: i32,
+    user: String,
+    group: String,
+}
+
+// Identifiers without quoting
+#[derive(Debug, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "PUBLIC.Artist", quote_identifiers = false)]
+struct UnquotedArtist {
+    #[orm(pk, generated)]
+    id: i32,
+    name: String,
+    #[orm(generated)]
+    slug: String,
+}
+
+// Queries checked at runtime
+#[derive(Debug, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.release", checked = false)]
+struct UncheckedRelease {
+    #[orm(pk, generated)]
+    id: i32,
+    name: String,
+    #[orm(generated)]
+    slug: String,
+    #[orm(deref)]
+    synonyms: Option<Vec<String>>,
+}
+
+#[derive(Debug, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.person", checked = false)]
+struct UncheckedPerson {
+    #[orm(pk)]
+    name: String,
+    #[orm(cast = Mood)]
+    current_mood: Mood,
+}
+
+#[derive(Debug, Entity, PartialEq, Eq, FromRow)]
+#[orm(
+    table = "public.soft_delete",
+    soft_delete = "deleted_at",
+    checked = false
+)]
+struct UncheckedSoftDelete {
+    #[orm(pk, generated)]
+    id: i32,
+    value: i32,
+    #[orm(default)]
+    deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.generated_only", checked = false)]
+struct UncheckedGeneratedOnly {
+    #[orm(pk, generated)]
+    id: i32,
+}
+
+#[derive(Debug, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.log_line", checked = false)]
+struct UncheckedLogLine {
+    message: String,
+    level: i32,
+}
+
+// Conflict targets
+#[derive(Debug, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.tag", conflict(fields(name)))]
+struct Tag {
+    #[orm(pk, generated)]
+    id: i32,
+    name: String,
+    #[orm(default)]
+    color: String,
+}
+
+#[derive(Debug, Entity, PartialEq, Eq, FromRow)]
+#[orm(table = "public.tag", conflict(constraint = "tag_name_key"))]
+struct ConstraintTag {
+    #[orm(pk, generated)]
+    id: i32,
+    name: String,
+    #[orm(default)]
+    color: String,
+}
+
 // Regression with optional primitive values
 // TODO: This table is not defined?
 // #[derive(Debug, Entity, PartialEq, Eq, FromRow)]
@@ -129,6 +352,60 @@ struct SoftDelete {
 //     boolean: Option<bool>,
 // }
 
+// Fixture helpers
+async fn insert_artists(pool: &PgPool, names: &[&str]) -> Vec<Artist> {
+    InsertableArtist::insert_many(
+        &names
+            .iter()
+            .map(|name| InsertableArtist {
+                name: name.to_string(),
+            })
+            .collect::<Vec<_>>(),
+        pool,
+    )
+    .await
+    .expect("Failed to insert artists")
+}
+
+async fn insert_release(pool: &PgPool, name: &str) -> Release {
+    InsertableRelease {
+        name: name.to_string(),
+        synonyms: None,
+    }
+    .insert(pool)
+    .await
+    .expect("Failed to insert release")
+}
+
+async fn insert_releases(pool: &PgPool, count: usize) -> Vec<Release> {
+    InsertableRelease::insert_many(
+        &(0..count)
+            .map(|idx| InsertableRelease {
+                name: format!("Testings {idx}"),
+                synonyms: None,
+            })
+            .collect::<Vec<_>>(),
+        pool,
+    )
+    .await
+    .expect("Failed to insert releases")
+}
+
+async fn insert_soft_deletes(pool: &PgPool, values: &[i32]) -> Vec<SoftDelete> {
+    InsertableSoftDelete::insert_many(
+        &values
+            .iter()
+            .map(|value| InsertableSoftDelete {
+                value: *value,
+                deleted_at: gremlin_orm::Defaultable::Default,
+            })
+            .collect::<Vec<_>>(),
+        pool,
+    )
+    .await
+    .expect("Failed to insert soft deletes")
+}
+
 mod insert {
     use gremlin_orm::Defaultable;
 
@@ -200,6 +477,23 @@ mod insert {
         check!(stored.synonyms == Some(vec!["Release".to_string()]));
     }
 
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_insert_an_entity_with_only_pk_fields(pool: PgPool) {
+        let entity = InsertableArtistType {
+            name: "Band".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        check!(
+            entity
+                == ArtistType {
+                    name: "Band".to_string()
+                }
+        );
+    }
+
     #[sqlx::test(fixtures("../resources/data/schema.sql"))]
     async fn it_should_generate_an_unit_struct_if_no_fields_can_be_inserted(pool: PgPool) {
         InsertableGeneratedOnly
@@ -267,153 +561,454 @@ mod insert {
     }
 }
 
-mod update {
+mod insert_many {
+    use gremlin_orm::Defaultable;
+
     use super::*;
 
     #[sqlx::test(fixtures("../resources/data/schema.sql"))]
-    async fn it_should_update_a_entity(pool: PgPool) {
-        let artist = InsertableArtist {
-            name: "Testings".to_string(),
-        }
-        .insert(&pool)
-        .await
-        .expect("Failed to insert artist");
-
-        let artist = UpdatableArtist {
-            id: artist.id,
-            name: "Updated".to_string(),
-        }
-        .update(&pool)
+    async fn it_should_insert_many_entities_in_order(pool: PgPool) {
+        let artists = InsertableArtist::insert_many(
+            &[
+                InsertableArtist {
+                    name: "Testings 1".to_string(),
+                },
+                InsertableArtist {
+                    name: "Testings 2".to_string(),
+                },
+            ],
+            &pool,
+        )
         .await
-        .expect("Failed to update artist");
+        .expect("Failed to insert artists");
 
-        check!(artist.name == "Updated".to_string());
-        check!(artist.slug == "updated".to_string());
+        check!(artists.len() == 2);
+        check!(artists[0].slug == "testings-1".to_string());
+        check!(artists[1].slug == "testings-2".to_string());
 
-        let artist = ArtistPk::from(artist)
-            .fetch(&pool)
-            .await
-            .expect("Failed to fetch artist")
-            .expect("Could not find artist");
+        let stored = Artist::stream(&pool)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
 
-        check!(artist.name == "Updated".to_string());
-        check!(artist.slug == "updated".to_string());
+        check!(stored == artists);
     }
 
     #[sqlx::test(fixtures("../resources/data/schema.sql"))]
-    async fn it_should_generate_a_from_implementation(pool: PgPool) {
-        let artist = InsertableArtist {
-            name: "Testings".to_string(),
-        }
-        .insert(&pool)
+    async fn it_should_use_the_default_per_row(pool: PgPool) {
+        let entities = InsertableDefaultable::insert_many(
+            &[
+                InsertableDefaultable {
+                    name: Defaultable::Value("Some name".to_string()),
+                },
+                InsertableDefaultable {
+                    name: Defaultable::Default,
+                },
+            ],
+            &pool,
+        )
         .await
-        .expect("Failed to insert artist");
+        .expect("Failed to insert entities");
 
-        let mut updatable = UpdatableArtist::from(artist);
-        updatable.name = "Updated".to_string();
+        check!(entities[0].name == "Some name".to_string());
+        check!(entities[1].name == "This is the default".to_string());
+    }
 
-        let artist = updatable
-            .update(&pool)
-            .await
-            .expect("Failed to update artist");
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_insert_many_entities_without_insertable_fields(pool: PgPool) {
+        let entities = InsertableGeneratedOnly::insert_many(
+            &[InsertableGeneratedOnly, InsertableGeneratedOnly],
+            &pool,
+        )
+        .await
+        .expect("Failed to insert entities");
 
-        check!(artist.name == "Updated".to_string());
-        check!(artist.slug == "updated".to_string());
+        check!(entities == vec![GeneratedOnly { id: 1 }, GeneratedOnly { id: 2 }]);
+    }
 
-        let artist = ArtistPk::from(artist)
-            .fetch(&pool)
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_split_values_exceeding_the_bind_parameter_limit(pool: PgPool) {
+        let people = (0..40_000)
+            .map(|idx| InsertablePerson {
+                name: format!("Human {idx}"),
+                current_mood: Mood::Ok,
+            })
+            .collect::<Vec<_>>();
+
+        let inserted = InsertablePerson::insert_many(&people, &pool)
             .await
-            .expect("Failed to fetch artist")
-            .expect("Could not find artist");
+            .expect("Failed to insert entities");
 
-        check!(artist.name == "Updated".to_string());
-        check!(artist.slug == "updated".to_string());
+        check!(inserted.len() == 40_000);
+        check!(inserted[39_999].name == "Human 39999".to_string());
     }
 
     #[sqlx::test(fixtures("../resources/data/schema.sql"))]
-    async fn it_should_update_a_enum(pool: PgPool) {
-        let entity = InsertablePerson {
-            name: "Human".to_string(),
-            current_mood: Mood::Ok,
-        }
-        .insert(&pool)
-        .await
-        .expect("Failed to insert entity");
+    async fn it_should_not_insert_any_entity_if_one_fails(pool: PgPool) {
+        let result = InsertableArtistType::insert_many(
+            &[
+                InsertableArtistType {
+                    name: "Band".to_string(),
+                },
+                InsertableArtistType {
+                    name: "Band".to_string(),
+                },
+            ],
+            &pool,
+        )
+        .await;
 
-        let mut updatable = UpdatablePerson::from(entity);
-        updatable.current_mood = Mood::Happy;
-        let entity = updatable
-            .update(&pool)
-            .await
-            .expect("Failed to update entity");
+        assert2::let_assert!(Err(gremlin_orm::Error::UniqueViolation { .. }) = result);
 
-        check!(entity.name == "Human".to_string());
-        check!(entity.current_mood == Mood::Happy);
+        let stored = ArtistType::stream(&pool).collect::<Vec<_>>().await;
+
+        check!(stored.is_empty());
     }
 
     #[sqlx::test(fixtures("../resources/data/schema.sql"))]
-    async fn it_should_support_connections(pool: PgPool) {
-        let mut connection = pool.acquire().await.expect("Could not get a connection");
+    async fn it_should_support_transactions(pool: PgPool) {
+        let mut tx = pool.begin().await.expect("Could not start a transaction");
 
-        let entity = InsertablePerson {
-            name: "Human".to_string(),
-            current_mood: Mood::Ok,
-        }
-        .insert(&mut *connection)
+        InsertableArtist::insert_many(
+            &[InsertableArtist {
+                name: "Testings".to_string(),
+            }],
+            &mut *tx,
+        )
         .await
-        .expect("Failed to insert entity");
+        .expect("Failed to insert artists");
 
-        let mut updatable = UpdatablePerson::from(entity);
-        updatable.current_mood = Mood::Happy;
-        let entity = updatable
-            .update(&mut *connection)
-            .await
-            .expect("Failed to update entity");
+        tx.rollback().await.expect("Failed to roll back");
 
-        check!(entity.name == "Human".to_string());
-        check!(entity.current_mood == Mood::Happy);
+        let stored = Artist::stream(&pool).collect::<Vec<_>>().await;
+
+        check!(stored.is_empty());
     }
 
     #[sqlx::test(fixtures("../resources/data/schema.sql"))]
-    async fn it_should_return_row_not_found_if_the_entity_is_soft_deleted(pool: PgPool) {
-        let result = sqlx::query!(
-            "INSERT INTO soft_delete (deleted_at, value) VALUES (NOW(), 0) RETURNING id"
-        )
-        .fetch_one(&pool)
-        .await
-        .expect("Failed to insert entity");
+    async fn it_should_insert_a_stream_of_entities(pool: PgPool) {
+        let values = futures::stream::iter((0..3).map(|idx| InsertableArtist {
+            name: format!("Testings {idx}"),
+        }));
 
-        let result = UpdatableSoftDelete {
-            id: result.id,
-            value: 0,
-        }
-        .update(&pool)
-        .await;
+        let inserted = InsertableArtist::insert_many_stream(values, &pool)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        check!(inserted.len() == 3);
+
+        let stored = Artist::stream(&pool)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
 
-        assert2::let_assert!(Err(sqlx::error::Error::RowNotFound) = result);
+        check!(stored == inserted);
     }
 }
 
-mod delete {
+mod copy {
     use super::*;
 
     #[sqlx::test(fixtures("../resources/data/schema.sql"))]
-    async fn it_should_delete_the_entity(pool: PgPool) {
-        let artist = InsertableArtist {
-            name: "Testings".to_string(),
-        }
-        .insert(&pool)
-        .await
-        .expect("Failed to insert artist");
-
-        artist.delete(&pool).await.expect("Failed to delete artist");
+    async fn it_should_copy_entities(pool: PgPool) {
+        let values = futures::stream::iter((0..1000).map(|idx| InsertableArtist {
+            name: format!("Testings {idx}"),
+        }));
 
-        let result = sqlx::query_as!(Artist, "SELECT * FROM artist WHERE id = $1", artist.id)
-            .fetch_optional(&pool)
+        let copied = InsertableArtist::copy_in(values, &pool)
             .await
-            .expect("Failed to fetch artist");
+            .expect("Failed to copy artists");
 
-        check!(let None = result);
+        check!(copied == 1000);
+
+        let stored = Artist::stream(&pool)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        check!(stored.len() == 1000);
+        check!(stored[999].slug == "testings-999".to_string());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_copy_arrays_and_nulls(pool: PgPool) {
+        let values = futures::stream::iter([
+            InsertableRelease {
+                name: "Testing 1".to_string(),
+                synonyms: Some(vec!["Test".to_string(), "Testing".to_string()]),
+            },
+            InsertableRelease {
+                name: "Testing 2".to_string(),
+                synonyms: None,
+            },
+        ]);
+
+        InsertableRelease::copy_in(values, &pool)
+            .await
+            .expect("Failed to copy releases");
+
+        let stored = Release::stream(&pool)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        check!(stored[0].synonyms == Some(vec!["Test".to_string(), "Testing".to_string()]));
+        check!(stored[1].synonyms == None);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_copy_enums(pool: PgPool) {
+        let values = futures::stream::iter([InsertablePerson {
+            name: "Tester".to_string(),
+            current_mood: Mood::Happy,
+        }]);
+
+        InsertablePerson::copy_in(values, &pool)
+            .await
+            .expect("Failed to copy people");
+
+        let stored = Person::stream(&pool)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        check!(stored[0].current_mood == Mood::Happy);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_reject_default_values(pool: PgPool) {
+        let values = futures::stream::iter([
+            InsertableDefaultable {
+                name: gremlin_orm::Defaultable::Value("Some name".to_string()),
+            },
+            InsertableDefaultable {
+                name: gremlin_orm::Defaultable::Default,
+            },
+        ]);
+
+        let result = InsertableDefaultable::copy_in(values, &pool).await;
+
+        check!(let Err(gremlin_orm::Error::Database(sqlx::Error::Encode(_))) = result);
+
+        let stored = Defaultable::stream(&pool)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        check!(stored.is_empty());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_set_timestamps(pool: PgPool) {
+        let values = futures::stream::iter([
+            InsertablePost {
+                title: "First".to_string(),
+            },
+            InsertablePost {
+                title: "Second".to_string(),
+            },
+        ]);
+
+        InsertablePost::copy_in(values, &pool)
+            .await
+            .expect("Failed to copy posts");
+
+        let inserted = InsertablePost {
+            title: "Inserted".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert post");
+
+        let stored = Post::stream(&pool)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        let drift = (inserted.created_at - stored[0].created_at).abs();
+
+        check!(stored[0].created_at == stored[0].updated_at);
+        check!(stored[0].created_at == stored[1].created_at);
+        check!(drift < chrono::TimeDelta::minutes(1));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_use_the_timestamp_of_the_database(pool: PgPool) {
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+
+        InsertablePost::copy_in(
+            futures::stream::iter([InsertablePost {
+                title: "Copied".to_string(),
+            }]),
+            &mut *tx,
+        )
+        .await
+        .expect("Failed to copy posts");
+
+        let inserted = InsertablePost {
+            title: "Inserted".to_string(),
+        }
+        .insert(&mut *tx)
+        .await
+        .expect("Failed to insert post");
+
+        let stored = Post::stream(&mut *tx)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        check!(stored[0].created_at == inserted.created_at);
+    }
+}
+
+mod update {
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_update_a_entity(pool: PgPool) {
+        let artist = InsertableArtist {
+            name: "Testings".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        let artist = UpdatableArtist {
+            id: artist.id,
+            name: "Updated".to_string(),
+        }
+        .update(&pool)
+        .await
+        .expect("Failed to update artist");
+
+        check!(artist.name == "Updated".to_string());
+        check!(artist.slug == "updated".to_string());
+
+        let artist = ArtistPk::from(artist)
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch artist")
+            .expect("Could not find artist");
+
+        check!(artist.name == "Updated".to_string());
+        check!(artist.slug == "updated".to_string());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_generate_a_from_implementation(pool: PgPool) {
+        let artist = InsertableArtist {
+            name: "Testings".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        let mut updatable = UpdatableArtist::from(artist);
+        updatable.name = "Updated".to_string();
+
+        let artist = updatable
+            .update(&pool)
+            .await
+            .expect("Failed to update artist");
+
+        check!(artist.name == "Updated".to_string());
+        check!(artist.slug == "updated".to_string());
+
+        let artist = ArtistPk::from(artist)
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch artist")
+            .expect("Could not find artist");
+
+        check!(artist.name == "Updated".to_string());
+        check!(artist.slug == "updated".to_string());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_update_a_enum(pool: PgPool) {
+        let entity = InsertablePerson {
+            name: "Human".to_string(),
+            current_mood: Mood::Ok,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let mut updatable = UpdatablePerson::from(entity);
+        updatable.current_mood = Mood::Happy;
+        let entity = updatable
+            .update(&pool)
+            .await
+            .expect("Failed to update entity");
+
+        check!(entity.name == "Human".to_string());
+        check!(entity.current_mood == Mood::Happy);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_support_connections(pool: PgPool) {
+        let mut connection = pool.acquire().await.expect("Could not get a connection");
+
+        let entity = InsertablePerson {
+            name: "Human".to_string(),
+            current_mood: Mood::Ok,
+        }
+        .insert(&mut *connection)
+        .await
+        .expect("Failed to insert entity");
+
+        let mut updatable = UpdatablePerson::from(entity);
+        updatable.current_mood = Mood::Happy;
+        let entity = updatable
+            .update(&mut *connection)
+            .await
+            .expect("Failed to update entity");
+
+        check!(entity.name == "Human".to_string());
+        check!(entity.current_mood == Mood::Happy);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_return_row_not_found_if_the_entity_is_soft_deleted(pool: PgPool) {
+        let result = sqlx::query!(
+            "INSERT INTO soft_delete (deleted_at, value) VALUES (NOW(), 0) RETURNING id"
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let result = UpdatableSoftDelete {
+            id: result.id,
+            value: 0,
+        }
+        .update(&pool)
+        .await;
+
+        assert2::let_assert!(Err(gremlin_orm::Error::NotFound) = result);
+    }
+}
+
+mod delete {
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_delete_the_entity(pool: PgPool) {
+        let artist = InsertableArtist {
+            name: "Testings".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        artist.delete(&pool).await.expect("Failed to delete artist");
+
+        let result = sqlx::query_as!(Artist, "SELECT * FROM artist WHERE id = $1", artist.id)
+            .fetch_optional(&pool)
+            .await
+            .expect("Failed to fetch artist");
+
+        check!(let None = result);
     }
 
     #[sqlx::test(fixtures("../resources/data/schema.sql"))]
@@ -709,3 +1304,3174 @@ mod fetch {
         assert2::let_assert!(Ok(None) = entity);
     }
 }
+
+mod fetch_many {
+    use gremlin_orm::Defaultable;
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_fetch_all_existing_entities(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B", "C"]).await;
+
+        let mut fetched = ArtistPk::fetch_many(
+            &[
+                ArtistPk::from(&artists[0]),
+                ArtistPk::from(&artists[2]),
+                ArtistPk { id: 999 },
+            ],
+            &pool,
+        )
+        .await
+        .expect("Failed to fetch artists");
+
+        fetched.sort_by_key(|artist| artist.id);
+
+        check!(fetched.len() == 2);
+        check!(fetched[0] == artists[0]);
+        check!(fetched[1] == artists[2]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_return_nothing_without_keys(pool: PgPool) {
+        insert_artists(&pool, &["A"]).await;
+
+        let fetched = ArtistPk::fetch_many(&[], &pool)
+            .await
+            .expect("Failed to fetch artists");
+
+        check!(fetched.is_empty());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_key_the_entities_by_their_pk(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B"]).await;
+
+        let fetched =
+            ArtistPk::fetch_many_map(&[ArtistPk::from(&artists[1]), ArtistPk { id: 999 }], &pool)
+                .await
+                .expect("Failed to fetch artists");
+
+        check!(fetched.len() == 1);
+        check!(fetched.get(&artists[1].id) == Some(&artists[1]));
+        check!(!fetched.contains_key(&999));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_match_composite_keys_pairwise(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B"]).await;
+
+        let releases = InsertableRelease::insert_many(
+            &[
+                InsertableRelease {
+                    name: "Testings 1".to_string(),
+                    synonyms: None,
+                },
+                InsertableRelease {
+                    name: "Testings 2".to_string(),
+                    synonyms: None,
+                },
+            ],
+            &pool,
+        )
+        .await
+        .expect("Failed to insert releases");
+
+        for (artist, release) in artists.iter().zip(&releases) {
+            InsertableArtistRelease {
+                artist_id: artist.id,
+                release_id: release.id,
+            }
+            .insert(&pool)
+            .await
+            .expect("Failed to insert artist_release");
+        }
+
+        let fetched = ArtistReleasePk::fetch_many(
+            &[
+                ArtistReleasePk {
+                    artist_id: artists[0].id,
+                    release_id: releases[0].id,
+                },
+                ArtistReleasePk {
+                    artist_id: artists[0].id,
+                    release_id: releases[1].id,
+                },
+            ],
+            &pool,
+        )
+        .await
+        .expect("Failed to fetch artist releases");
+
+        check!(
+            fetched
+                == vec![ArtistRelease {
+                    artist_id: artists[0].id,
+                    release_id: releases[0].id,
+                }]
+        );
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_key_composite_keys_by_a_tuple(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A"]).await;
+        let releases = insert_releases(&pool, 1).await;
+
+        let link = InsertableArtistRelease {
+            artist_id: artists[0].id,
+            release_id: releases[0].id,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist_release");
+
+        let fetched = ArtistReleasePk::fetch_many_map(
+            &[ArtistReleasePk {
+                artist_id: link.artist_id,
+                release_id: link.release_id,
+            }],
+            &pool,
+        )
+        .await
+        .expect("Failed to fetch artist releases");
+
+        check!(fetched.get(&(link.artist_id, link.release_id)) == Some(&link));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_not_fetch_soft_deleted_entities(pool: PgPool) {
+        let entity = InsertableSoftDelete {
+            value: 1,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let pk = SoftDeletePk::from(&entity);
+
+        entity.delete(&pool).await.expect("Failed to delete entity");
+
+        let fetched = SoftDeletePk::fetch_many(&[pk], &pool)
+            .await
+            .expect("Failed to fetch entities");
+
+        check!(fetched.is_empty());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_fetch_many_without_checking(pool: PgPool) {
+        let entity = InsertableUncheckedSoftDelete {
+            value: 1,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let fetched =
+            UncheckedSoftDeletePk::fetch_many(&[UncheckedSoftDeletePk::from(&entity)], &pool)
+                .await
+                .expect("Failed to fetch entities");
+
+        check!(fetched == vec![entity]);
+    }
+}
+
+mod rename {
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_insert_into_renamed_columns(pool: PgPool) {
+        let account = InsertableLegacyAccount {
+            display_name: "Testings".to_string(),
+            email: Some("test@example.com".to_string()),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert account");
+
+        let stored = sqlx::query!(
+            r#"SELECT "displayName" AS display_name, "EMAIL" AS email FROM legacy_account WHERE "accountId" = $1"#,
+            account.account_id
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch account");
+
+        check!(stored.display_name == "Testings".to_string());
+        check!(stored.email == Some("test@example.com".to_string()));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_fetch_renamed_columns(pool: PgPool) {
+        let account = InsertableLegacyAccount {
+            display_name: "Testings".to_string(),
+            email: None,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert account");
+
+        let fetched = LegacyAccountPk {
+            account_id: account.account_id,
+        }
+        .fetch(&pool)
+        .await
+        .expect("Failed to fetch account")
+        .expect("Could not find account");
+
+        check!(fetched == account);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_update_renamed_columns(pool: PgPool) {
+        let account = InsertableLegacyAccount {
+            display_name: "Testings".to_string(),
+            email: None,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert account");
+
+        let mut updatable = UpdatableLegacyAccount::from(account);
+        updatable.display_name = "Updated".to_string();
+        updatable.email = Some("test@example.com".to_string());
+
+        let account = updatable
+            .update(&pool)
+            .await
+            .expect("Failed to update account");
+
+        check!(account.display_name == "Updated".to_string());
+        check!(account.email == Some("test@example.com".to_string()));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_stream_and_delete_renamed_columns(pool: PgPool) {
+        let account = InsertableLegacyAccount {
+            display_name: "Testings".to_string(),
+            email: None,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert account");
+
+        let accounts = LegacyAccount::stream(&pool)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        check!(accounts == vec![account]);
+
+        accounts[0]
+            .delete(&pool)
+            .await
+            .expect("Failed to delete account");
+
+        let accounts = LegacyAccount::stream(&pool).collect::<Vec<_>>().await;
+
+        check!(accounts.is_empty());
+    }
+}
+
+mod quoting {
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_quote_reserved_identifiers(pool: PgPool) {
+        let order = InsertableOrder {
+            user: "User".to_string(),
+            group: "Group".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert order");
+
+        check!(order.user == "User".to_string());
+        check!(order.group == "Group".to_string());
+
+        let mut updatable = UpdatableOrder::from(order);
+        updatable.group = "Updated".to_string();
+
+        let order = updatable
+            .update(&pool)
+            .await
+            .expect("Failed to update order");
+
+        let fetched = OrderPk { id: order.id }
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch order")
+            .expect("Could not find order");
+
+        check!(fetched == order);
+
+        let orders = Order::stream(&pool)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        check!(orders == vec![order]);
+
+        orders[0]
+            .delete(&pool)
+            .await
+            .expect("Failed to delete order");
+
+        let orders = Order::stream(&pool).collect::<Vec<_>>().await;
+
+        check!(orders.is_empty());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_use_the_table_as_written_when_quoting_is_disabled(pool: PgPool) {
+        let artist = InsertableUnquotedArtist {
+            name: "Testings".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        let fetched = ArtistPk { id: artist.id }
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch artist")
+            .expect("Could not find artist");
+
+        check!(fetched.name == artist.name);
+        check!(fetched.slug == artist.slug);
+    }
+}
+
+mod unchecked {
+    use gremlin_orm::QueryableEntity;
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_insert_update_and_fetch_an_entity(pool: PgPool) {
+        let release = InsertableUncheckedRelease {
+            name: "Testings".to_string(),
+            synonyms: Some(vec!["Release".to_string()]),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert release");
+
+        let mut updatable = UpdatableUncheckedRelease::from(release);
+        updatable.name = "Updated".to_string();
+
+        let release = updatable
+            .update(&pool)
+            .await
+            .expect("Failed to update release");
+
+        check!(release.slug == "updated".to_string());
+
+        let fetched = UncheckedReleasePk { id: release.id }
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch release")
+            .expect("Could not find release");
+
+        check!(fetched == release);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_stream_and_update_enums(pool: PgPool) {
+        let person = InsertableUncheckedPerson {
+            name: "Human".to_string(),
+            current_mood: Mood::Ok,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let mut updatable = UpdatableUncheckedPerson::from(person);
+        updatable.current_mood = Mood::Happy;
+        updatable
+            .update(&pool)
+            .await
+            .expect("Failed to update entity");
+
+        let people = UncheckedPerson::stream(&pool)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        check!(
+            people
+                == vec![UncheckedPerson {
+                    name: "Human".to_string(),
+                    current_mood: Mood::Happy
+                }]
+        );
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_soft_delete_entities(pool: PgPool) {
+        let entity = InsertableUncheckedSoftDelete {
+            value: 0,
+            deleted_at: gremlin_orm::Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        entity.delete(&pool).await.expect("Failed to delete entity");
+
+        let fetched = UncheckedSoftDeletePk { id: entity.id }
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch entity");
+
+        check!(let None = fetched);
+
+        let entities = UncheckedSoftDelete::stream(&pool).collect::<Vec<_>>().await;
+
+        check!(entities.is_empty());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_insert_default_values(pool: PgPool) {
+        let entity = InsertableUncheckedGeneratedOnly
+            .insert(&pool)
+            .await
+            .expect("Failed to insert entity");
+
+        check!(entity.id == 1);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_support_entities_without_primary_keys(pool: PgPool) {
+        let line = InsertableUncheckedLogLine {
+            message: "Started".to_string(),
+            level: 1,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let fetched = UncheckedLogLine::query()
+            .filter(UncheckedLogLine::columns().level.eq(1))
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query entities");
+
+        check!(fetched == vec![line]);
+    }
+}
+
+mod error {
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_classify_unique_violations(pool: PgPool) {
+        InsertableArtistType {
+            name: "Band".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let result = InsertableArtistType {
+            name: "Band".to_string(),
+        }
+        .insert(&pool)
+        .await;
+
+        assert2::let_assert!(Err(gremlin_orm::Error::UniqueViolation { constraint, .. }) = result);
+        check!(constraint == Some("artist_type_pkey".to_string()));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_classify_foreign_key_violations(pool: PgPool) {
+        let result = InsertableTrack {
+            release_id: Some(999),
+            name: "Testings".to_string(),
+        }
+        .insert(&pool)
+        .await;
+
+        assert2::let_assert!(
+            Err(gremlin_orm::Error::ForeignKeyViolation { constraint, .. }) = result
+        );
+        check!(constraint == Some("track_release_id_fkey".to_string()));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_classify_not_null_violations(pool: PgPool) {
+        let result = sqlx::query("INSERT INTO artist (name) VALUES (NULL)")
+            .execute(&pool)
+            .await
+            .map_err(gremlin_orm::Error::from);
+
+        assert2::let_assert!(Err(gremlin_orm::Error::NotNullViolation { column, .. }) = result);
+        check!(column == Some("name".to_string()));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_classify_check_violations(pool: PgPool) {
+        let result = InsertableUncheckedLogLine {
+            message: "Started".to_string(),
+            level: -1,
+        }
+        .insert(&pool)
+        .await;
+
+        assert2::let_assert!(Err(gremlin_orm::Error::CheckViolation { constraint, .. }) = result);
+        check!(constraint == Some("log_line_level_check".to_string()));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_convert_sqlx_errors_using_the_question_mark_operator(pool: PgPool) {
+        async fn fetch_missing(pool: &PgPool) -> Result<i32, gremlin_orm::Error> {
+            let id = sqlx::query_scalar!("SELECT id FROM artist WHERE id = 999")
+                .fetch_one(pool)
+                .await?;
+
+            Ok(id)
+        }
+
+        assert2::let_assert!(Err(gremlin_orm::Error::NotFound) = fetch_missing(&pool).await);
+    }
+}
+
+mod upsert {
+    use gremlin_orm::Defaultable;
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_update_the_conflicting_row_on_the_primary_key(pool: PgPool) {
+        InsertablePerson {
+            name: "Human".to_string(),
+            current_mood: Mood::Ok,
+        }
+        .upsert(&pool)
+        .await
+        .expect("Failed to upsert entity");
+
+        let entity = InsertablePerson {
+            name: "Human".to_string(),
+            current_mood: Mood::Happy,
+        }
+        .upsert(&pool)
+        .await
+        .expect("Failed to upsert entity");
+
+        check!(entity.current_mood == Mood::Happy);
+
+        let people = Person::stream(&pool).collect::<Vec<_>>().await;
+
+        check!(people.len() == 1);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_return_the_row_if_only_the_conflict_target_is_inserted(pool: PgPool) {
+        let first = InsertableArtistType {
+            name: "Band".to_string(),
+        }
+        .upsert(&pool)
+        .await
+        .expect("Failed to upsert entity");
+
+        let second = InsertableArtistType {
+            name: "Band".to_string(),
+        }
+        .upsert(&pool)
+        .await
+        .expect("Failed to upsert entity");
+
+        check!(first == second);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_use_the_configured_conflict_fields(pool: PgPool) {
+        let first = InsertableTag {
+            name: "Rock".to_string(),
+            color: Defaultable::Default,
+        }
+        .upsert(&pool)
+        .await
+        .expect("Failed to upsert tag");
+
+        check!(first.color == "black".to_string());
+
+        let second = InsertableTag {
+            name: "Rock".to_string(),
+            color: Defaultable::Value("red".to_string()),
+        }
+        .upsert(&pool)
+        .await
+        .expect("Failed to upsert tag");
+
+        check!(second.id == first.id);
+        check!(second.color == "red".to_string());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_not_update_default_values_on_conflict(pool: PgPool) {
+        let first = InsertableTag {
+            name: "Rock".to_string(),
+            color: Defaultable::Value("red".to_string()),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert tag");
+
+        let second = InsertableTag {
+            name: "Rock".to_string(),
+            color: Defaultable::Default,
+        }
+        .upsert(&pool)
+        .await
+        .expect("Failed to upsert tag");
+
+        check!(second == first);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_use_the_configured_conflict_constraint(pool: PgPool) {
+        let first = InsertableConstraintTag {
+            name: "Rock".to_string(),
+            color: Defaultable::Default,
+        }
+        .upsert(&pool)
+        .await
+        .expect("Failed to upsert tag");
+
+        let second = InsertableConstraintTag {
+            name: "Rock".to_string(),
+            color: Defaultable::Value("blue".to_string()),
+        }
+        .upsert(&pool)
+        .await
+        .expect("Failed to upsert tag");
+
+        check!(second.id == first.id);
+        check!(second.color == "blue".to_string());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_ignore_conflicting_rows(pool: PgPool) {
+        let first = InsertableConstraintTag {
+            name: "Rock".to_string(),
+            color: Defaultable::Value("red".to_string()),
+        }
+        .insert_or_ignore(&pool)
+        .await
+        .expect("Failed to insert tag");
+
+        assert2::let_assert!(Some(first) = first);
+
+        let second = InsertableConstraintTag {
+            name: "Rock".to_string(),
+            color: Defaultable::Value("blue".to_string()),
+        }
+        .insert_or_ignore(&pool)
+        .await
+        .expect("Failed to insert tag");
+
+        check!(let None = second);
+
+        let stored = ConstraintTagPk { id: first.id }
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch tag");
+
+        check!(stored == Some(first));
+    }
+}
+
+mod query {
+    use gremlin_orm::{Defaultable, QueryableEntity};
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_select_all_entities_without_filters(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B"]).await;
+
+        let fetched = Artist::query()
+            .order_by(Artist::columns().id)
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query artists");
+
+        check!(fetched == artists);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_filter_entities(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B", "C"]).await;
+        let columns = Artist::columns();
+
+        let fetched = Artist::query()
+            .filter(columns.name.eq("B"))
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query artists");
+
+        check!(fetched == vec![artists[1].clone()]);
+
+        let fetched = Artist::query()
+            .filter(columns.name.eq("A").or(columns.name.eq("C")))
+            .filter(!columns.id.eq(artists[2].id))
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query artists");
+
+        check!(fetched == vec![artists[0].clone()]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_filter_using_comparisons_and_patterns(pool: PgPool) {
+        let artists = insert_artists(&pool, &["Alpha", "Beta", "alphabet"]).await;
+        let columns = Artist::columns();
+
+        let fetched = Artist::query()
+            .filter(columns.name.ilike("alpha%"))
+            .filter(columns.id.gt(artists[0].id))
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query artists");
+
+        check!(fetched == vec![artists[2].clone()]);
+
+        let fetched = Artist::query()
+            .filter(columns.id.is_in([artists[0].id, artists[1].id]))
+            .filter(columns.name.like("B%"))
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query artists");
+
+        check!(fetched == vec![artists[1].clone()]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_compare_null_values_using_is_null(pool: PgPool) {
+        let release = InsertableRelease {
+            name: "Testings".to_string(),
+            synonyms: None,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert release");
+
+        let tracks = InsertableTrack::insert_many(
+            &[
+                InsertableTrack {
+                    release_id: Some(release.id),
+                    name: "Released".to_string(),
+                },
+                InsertableTrack {
+                    release_id: None,
+                    name: "Unreleased".to_string(),
+                },
+            ],
+            &pool,
+        )
+        .await
+        .expect("Failed to insert tracks");
+
+        let columns = Track::columns();
+
+        let unreleased = Track::query()
+            .filter(columns.release_id.eq(None))
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query tracks");
+
+        let released = Track::query()
+            .filter(columns.release_id.ne(None))
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query tracks");
+
+        check!(unreleased == vec![tracks[1].clone()]);
+        check!(released == vec![tracks[0].clone()]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_order_limit_and_offset(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B", "C", "D"]).await;
+
+        let fetched = Artist::query()
+            .order_by(Artist::columns().name.desc())
+            .limit(2)
+            .offset(1)
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query artists");
+
+        check!(fetched == vec![artists[2].clone(), artists[1].clone()]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_support_the_maximum_limit_and_offset(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B"]).await;
+
+        let fetched = Artist::query()
+            .order_by(Artist::columns().id.asc())
+            .limit(u64::MAX)
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query artists");
+
+        check!(fetched == artists);
+
+        let fetched = Artist::query()
+            .offset(u64::MAX)
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query artists");
+
+        check!(fetched.is_empty());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_fetch_an_optional_entity(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A"]).await;
+        let columns = Artist::columns();
+
+        let fetched = Artist::query()
+            .filter(columns.name.eq("A"))
+            .fetch_optional(&pool)
+            .await
+            .expect("Failed to query artist");
+
+        check!(fetched == Some(artists[0].clone()));
+
+        let fetched = Artist::query()
+            .filter(columns.name.eq("B"))
+            .fetch_optional(&pool)
+            .await
+            .expect("Failed to query artist");
+
+        check!(fetched == None);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_stream_entities(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B"]).await;
+
+        let fetched = Artist::query()
+            .filter(Artist::columns().name.ne("A"))
+            .stream(&pool)
+            .map(|result| result.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        check!(fetched == vec![artists[1].clone()]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_filter_nullable_columns(pool: PgPool) {
+        let release = InsertableRelease {
+            name: "Testings".to_string(),
+            synonyms: Some(vec!["Test".to_string()]),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert release");
+
+        InsertableRelease {
+            name: "Other".to_string(),
+            synonyms: None,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert release");
+
+        let fetched = Release::query()
+            .filter(Release::columns().synonyms.is_not_null())
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query releases");
+
+        check!(fetched == vec![release]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_decode_type_overrides(pool: PgPool) {
+        let person = InsertablePerson {
+            name: "Human".to_string(),
+            current_mood: Mood::Happy,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert person");
+
+        InsertablePerson {
+            name: "Other human".to_string(),
+            current_mood: Mood::Sad,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert person");
+
+        let fetched = Person::query()
+            .filter(Person::columns().current_mood.eq(Mood::Happy))
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query people");
+
+        check!(fetched == vec![person]);
+
+        let value = InsertableSomeJsonValue {
+            json_value: Json(SerializedValue {
+                content: "Json".to_string(),
+            }),
+            jsonb_value: Json(SerializedValue {
+                content: "Jsonb".to_string(),
+            }),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert value");
+
+        let fetched = SomeJsonValue::query()
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query values");
+
+        check!(fetched == vec![value]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_use_renamed_columns(pool: PgPool) {
+        let account = InsertableLegacyAccount {
+            display_name: "Tester".to_string(),
+            email: Some("tester@example.com".to_string()),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert account");
+
+        let fetched = LegacyAccount::query()
+            .filter(
+                LegacyAccount::columns()
+                    .email
+                    .eq("tester@example.com".to_string()),
+            )
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query accounts");
+
+        check!(fetched == vec![account]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_not_return_soft_deleted_entities(pool: PgPool) {
+        let deleted = InsertableUncheckedSoftDelete {
+            value: 1,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let entity = InsertableUncheckedSoftDelete {
+            value: 1,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        deleted
+            .delete(&pool)
+            .await
+            .expect("Failed to delete entity");
+
+        let fetched = UncheckedSoftDelete::query()
+            .filter(UncheckedSoftDelete::columns().value.eq(1))
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query entities");
+
+        check!(fetched == vec![entity]);
+    }
+}
+
+mod aggregate {
+    use gremlin_orm::QueryableEntity;
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_count_entities_which_are_not_soft_deleted(pool: PgPool) {
+        let mut entities = insert_soft_deletes(&pool, &[1, 2, 3]).await;
+
+        entities
+            .pop()
+            .unwrap()
+            .delete(&pool)
+            .await
+            .expect("Failed to delete entity");
+
+        let count = SoftDelete::count(&pool)
+            .await
+            .expect("Failed to count entities");
+
+        check!(count == 2);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_count_filtered_and_limited_queries(pool: PgPool) {
+        insert_soft_deletes(&pool, &[1, 2, 3, 4]).await;
+        let columns = SoftDelete::columns();
+
+        let count = SoftDelete::query()
+            .filter(columns.value.gt(1))
+            .count(&pool)
+            .await
+            .expect("Failed to count entities");
+
+        check!(count == 3);
+
+        let count = SoftDelete::query()
+            .limit(2)
+            .offset(1)
+            .count(&pool)
+            .await
+            .expect("Failed to count entities");
+
+        check!(count == 2);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_check_whether_an_entity_exists(pool: PgPool) {
+        let mut entities = insert_soft_deletes(&pool, &[1, 2]).await;
+        let deleted = entities.pop().unwrap();
+        let pk = SoftDeletePk::from(&deleted);
+
+        check!(pk.exists(&pool).await.expect("Failed to check entity"));
+
+        deleted
+            .delete(&pool)
+            .await
+            .expect("Failed to delete entity");
+
+        check!(!pk.exists(&pool).await.expect("Failed to check entity"));
+
+        let unchecked_pk = UncheckedSoftDeletePk { id: entities[0].id };
+
+        check!(
+            unchecked_pk
+                .exists(&pool)
+                .await
+                .expect("Failed to check entity")
+        );
+
+        let columns = SoftDelete::columns();
+
+        check!(
+            SoftDelete::query()
+                .filter(columns.value.eq(1))
+                .exists(&pool)
+                .await
+                .expect("Failed to check entities")
+        );
+        check!(
+            !SoftDelete::query()
+                .filter(columns.value.eq(2))
+                .exists(&pool)
+                .await
+                .expect("Failed to check entities")
+        );
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_compute_aggregates(pool: PgPool) {
+        let mut entities = insert_soft_deletes(&pool, &[3, 1, 5, 10]).await;
+
+        entities
+            .pop()
+            .unwrap()
+            .delete(&pool)
+            .await
+            .expect("Failed to delete entity");
+
+        let query = SoftDelete::query();
+        let columns = SoftDelete::columns();
+
+        check!(query.min(columns.value, &pool).await.unwrap() == Some(1));
+        check!(query.max(columns.value, &pool).await.unwrap() == Some(5));
+        check!(query.sum(columns.value, &pool).await.unwrap() == 9);
+
+        let query = query.filter(columns.value.gt(1));
+
+        check!(query.sum(columns.value, &pool).await.unwrap() == 8);
+
+        let query = query.filter(columns.value.gt(5));
+
+        check!(query.max(columns.value, &pool).await.unwrap() == None);
+        check!(query.sum(columns.value, &pool).await.unwrap() == 0);
+    }
+}
+
+mod page {
+    use gremlin_orm::{Defaultable, PageableEntity, QueryableEntity};
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_page_through_all_entities(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B", "C", "D", "E"]).await;
+
+        let first = Artist::page_after(None, 2, &pool)
+            .await
+            .expect("Failed to fetch page");
+
+        check!(first.items == artists[0..2]);
+        check!(first.next.as_ref().map(|pk| pk.id) == Some(artists[1].id));
+
+        let second = Artist::page_after(first.next, 2, &pool)
+            .await
+            .expect("Failed to fetch page");
+
+        check!(second.items == artists[2..4]);
+
+        let last = Artist::page_after(second.next, 2, &pool)
+            .await
+            .expect("Failed to fetch page");
+
+        check!(last.items == artists[4..]);
+        check!(last.next.is_none());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_not_return_a_cursor_for_an_exactly_full_last_page(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B"]).await;
+
+        let page = Artist::page_after(None, 2, &pool)
+            .await
+            .expect("Failed to fetch page");
+
+        check!(page.items == artists);
+        check!(page.next.is_none());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_reject_an_empty_limit(pool: PgPool) {
+        insert_artists(&pool, &["A", "B"]).await;
+
+        let result = Artist::page_after(None, 0, &pool).await;
+
+        assert2::let_assert!(Err(gremlin_orm::Error::InvalidPageLimit) = result);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_support_the_maximum_limit(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B", "C"]).await;
+
+        let page = Artist::page_after(None, u64::MAX, &pool)
+            .await
+            .expect("Failed to fetch page");
+
+        check!(page.items == artists);
+        check!(page.next.is_none());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_be_stable_under_concurrent_inserts(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B", "C"]).await;
+
+        let first = Artist::page_after(None, 2, &pool)
+            .await
+            .expect("Failed to fetch page");
+
+        let inserted = InsertableArtist {
+            name: "Inserted".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        let second = Artist::page_after(first.next, 2, &pool)
+            .await
+            .expect("Failed to fetch page");
+
+        check!(second.items == vec![artists[2].clone(), inserted]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_page_through_composite_keys(pool: PgPool) {
+        let artists = insert_artists(&pool, &["A", "B"]).await;
+
+        let releases = insert_releases(&pool, 2).await;
+
+        let mut links = vec![];
+
+        for artist in &artists {
+            for release in &releases {
+                links.push(InsertableArtistRelease {
+                    artist_id: artist.id,
+                    release_id: release.id,
+                });
+            }
+        }
+
+        let links = InsertableArtistRelease::insert_many(&links, &pool)
+            .await
+            .expect("Failed to insert artist releases");
+
+        let first = ArtistRelease::page_after(None, 3, &pool)
+            .await
+            .expect("Failed to fetch page");
+
+        check!(first.items == links[0..3]);
+
+        let second = ArtistRelease::page_after(first.next, 3, &pool)
+            .await
+            .expect("Failed to fetch page");
+
+        check!(second.items == links[3..]);
+        check!(second.next.is_none());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_page_through_filtered_entities_which_are_not_deleted(pool: PgPool) {
+        let mut entities = InsertableSoftDelete::insert_many(
+            &[1, 2, 3, 4].map(|value| InsertableSoftDelete {
+                value,
+                deleted_at: Defaultable::Default,
+            }),
+            &pool,
+        )
+        .await
+        .expect("Failed to insert entities");
+
+        let deleted = entities.remove(1);
+        deleted
+            .delete(&pool)
+            .await
+            .expect("Failed to delete entity");
+
+        let page = SoftDelete::query()
+            .filter(SoftDelete::columns().value.lt(4))
+            .page_after(None, 10, &pool)
+            .await
+            .expect("Failed to fetch page");
+
+        check!(page.items == entities[0..2]);
+        check!(page.next.is_none());
+    }
+}
+
+mod relation {
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_load_the_entity_a_foreign_key_belongs_to(pool: PgPool) {
+        let artist = InsertableArtist {
+            name: "Testings".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        let release = insert_release(&pool, "Testings").await;
+
+        let link = InsertableArtistRelease {
+            artist_id: artist.id,
+            release_id: release.id,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist release");
+
+        check!(link.artist(&pool).await.unwrap() == Some(artist));
+        check!(link.release(&pool).await.unwrap() == Some(release));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_load_the_entities_which_belong_to_an_entity(pool: PgPool) {
+        let release = insert_release(&pool, "Testings").await;
+        let other = insert_release(&pool, "Other").await;
+
+        let tracks = InsertableTrack::insert_many(
+            &[
+                InsertableTrack {
+                    release_id: Some(release.id),
+                    name: "First".to_string(),
+                },
+                InsertableTrack {
+                    release_id: Some(other.id),
+                    name: "Other".to_string(),
+                },
+                InsertableTrack {
+                    release_id: Some(release.id),
+                    name: "Second".to_string(),
+                },
+            ],
+            &pool,
+        )
+        .await
+        .expect("Failed to insert tracks");
+
+        let mut loaded = release.tracks(&pool).await.expect("Failed to load tracks");
+        loaded.sort_by_key(|track| track.id);
+
+        check!(loaded == vec![tracks[0].clone(), tracks[2].clone()]);
+        check!(release.artist_links(&pool).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_not_load_anything_for_unset_foreign_keys(pool: PgPool) {
+        insert_release(&pool, "Testings").await;
+
+        let track = InsertableTrack {
+            release_id: None,
+            name: "Loose".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert track");
+
+        check!(track.release(&pool).await.unwrap() == None);
+    }
+}
+
+mod load_related {
+    use gremlin_orm::QueryableEntity;
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_group_the_related_entities_per_entity(pool: PgPool) {
+        let releases = insert_releases(&pool, 3).await;
+
+        let tracks = InsertableTrack::insert_many(
+            &[
+                InsertableTrack {
+                    release_id: Some(releases[0].id),
+                    name: "First".to_string(),
+                },
+                InsertableTrack {
+                    release_id: Some(releases[2].id),
+                    name: "Other".to_string(),
+                },
+                InsertableTrack {
+                    release_id: Some(releases[0].id),
+                    name: "Second".to_string(),
+                },
+            ],
+            &pool,
+        )
+        .await
+        .expect("Failed to insert tracks");
+
+        let mut loaded = Release::load_related::<Track>(&releases, &pool)
+            .await
+            .expect("Failed to load tracks");
+
+        loaded[0].sort_by_key(|track| track.id);
+
+        check!(
+            loaded
+                == vec![
+                    vec![tracks[0].clone(), tracks[2].clone()],
+                    vec![],
+                    vec![tracks[1].clone()]
+                ]
+        );
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_load_the_same_entity_for_multiple_entities(pool: PgPool) {
+        let releases = insert_releases(&pool, 1).await;
+
+        let tracks = InsertableTrack::insert_many(
+            &[
+                InsertableTrack {
+                    release_id: Some(releases[0].id),
+                    name: "First".to_string(),
+                },
+                InsertableTrack {
+                    release_id: None,
+                    name: "Loose".to_string(),
+                },
+                InsertableTrack {
+                    release_id: Some(releases[0].id),
+                    name: "Second".to_string(),
+                },
+            ],
+            &pool,
+        )
+        .await
+        .expect("Failed to insert tracks");
+
+        let loaded = Track::load_related::<Release>(&tracks, &pool)
+            .await
+            .expect("Failed to load releases");
+
+        check!(loaded == vec![releases.clone(), vec![], releases]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_load_nothing_without_entities(pool: PgPool) {
+        let loaded = Release::load_related::<Track>(&[], &pool)
+            .await
+            .expect("Failed to load tracks");
+
+        check!(loaded.is_empty());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_match_composite_keys(pool: PgPool) {
+        let artist = InsertableArtist {
+            name: "Testings".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        let releases = insert_releases(&pool, 2).await;
+
+        let links = InsertableArtistRelease::insert_many(
+            &releases
+                .iter()
+                .map(|release| InsertableArtistRelease {
+                    artist_id: artist.id,
+                    release_id: release.id,
+                })
+                .collect::<Vec<_>>(),
+            &pool,
+        )
+        .await
+        .expect("Failed to insert artist releases");
+
+        let credits = InsertableCredit::insert_many(
+            &[
+                InsertableCredit {
+                    artist_id: artist.id,
+                    release_id: releases[1].id,
+                    role: "Producer".to_string(),
+                },
+                InsertableCredit {
+                    artist_id: artist.id,
+                    release_id: releases[0].id,
+                    role: "Vocals".to_string(),
+                },
+            ],
+            &pool,
+        )
+        .await
+        .expect("Failed to insert credits");
+
+        let loaded = ArtistRelease::load_related::<Credit>(&links, &pool)
+            .await
+            .expect("Failed to load credits");
+
+        check!(loaded == vec![vec![credits[1].clone()], vec![credits[0].clone()]]);
+        check!(links[1].credits(&pool).await.unwrap() == vec![credits[0].clone()]);
+    }
+}
+
+mod join {
+    use gremlin_orm::JoinEntity;
+
+    use super::*;
+
+    async fn insert_fixtures(pool: &PgPool, releases: usize) -> (Artist, Vec<Release>) {
+        let mut artists = insert_artists(pool, &["Testings"]).await;
+
+        (artists.remove(0), insert_releases(pool, releases).await)
+    }
+
+    async fn linked(pool: &PgPool, artist: &Artist) -> Vec<i32> {
+        let mut linked = ArtistRelease::linked_right(&artist.into(), pool)
+            .await
+            .expect("Failed to fetch linked releases")
+            .into_iter()
+            .map(|release| release.id)
+            .collect::<Vec<_>>();
+
+        linked.sort();
+        linked
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_link_entities_once(pool: PgPool) {
+        let (artist, releases) = insert_fixtures(&pool, 1).await;
+
+        let first = ArtistRelease::link(&(&artist).into(), &(&releases[0]).into(), &pool)
+            .await
+            .expect("Failed to link");
+        let second = ArtistRelease::link(&(&artist).into(), &(&releases[0]).into(), &pool)
+            .await
+            .expect("Failed to link");
+
+        check!(first);
+        check!(!second);
+        check!(linked(&pool, &artist).await == vec![releases[0].id]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_unlink_entities(pool: PgPool) {
+        let (artist, releases) = insert_fixtures(&pool, 2).await;
+
+        for release in &releases {
+            ArtistRelease::link(&(&artist).into(), &release.into(), &pool)
+                .await
+                .expect("Failed to link");
+        }
+
+        let first = ArtistRelease::unlink(&(&artist).into(), &(&releases[0]).into(), &pool)
+            .await
+            .expect("Failed to unlink");
+        let second = ArtistRelease::unlink(&(&artist).into(), &(&releases[0]).into(), &pool)
+            .await
+            .expect("Failed to unlink");
+
+        check!(first);
+        check!(!second);
+        check!(linked(&pool, &artist).await == vec![releases[1].id]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_replace_the_links(pool: PgPool) {
+        let (artist, releases) = insert_fixtures(&pool, 3).await;
+
+        ArtistRelease::set_links(
+            &(&artist).into(),
+            &[(&releases[0]).into(), (&releases[1]).into()],
+            &pool,
+        )
+        .await
+        .expect("Failed to set links");
+
+        check!(linked(&pool, &artist).await == vec![releases[0].id, releases[1].id]);
+
+        ArtistRelease::set_links(
+            &(&artist).into(),
+            &[
+                (&releases[1]).into(),
+                (&releases[2]).into(),
+                (&releases[2]).into(),
+            ],
+            &pool,
+        )
+        .await
+        .expect("Failed to set links");
+
+        check!(linked(&pool, &artist).await == vec![releases[1].id, releases[2].id]);
+
+        ArtistRelease::set_links(&(&artist).into(), &[], &pool)
+            .await
+            .expect("Failed to set links");
+
+        check!(linked(&pool, &artist).await.is_empty());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_fetch_the_linked_left_entities(pool: PgPool) {
+        let (artist, releases) = insert_fixtures(&pool, 2).await;
+
+        let other = InsertableArtist {
+            name: "Other".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        ArtistRelease::link(&(&artist).into(), &(&releases[0]).into(), &pool)
+            .await
+            .expect("Failed to link");
+        ArtistRelease::link(&(&other).into(), &(&releases[1]).into(), &pool)
+            .await
+            .expect("Failed to link");
+
+        let artists = ArtistRelease::linked_left(&(&releases[0]).into(), &pool)
+            .await
+            .expect("Failed to fetch linked artists");
+
+        check!(artists == vec![artist]);
+    }
+}
+
+mod version {
+    use gremlin_orm::{Defaultable, Error, UpsertableEntity};
+
+    use super::*;
+
+    async fn insert_document(pool: &PgPool) -> Document {
+        InsertableDocument {
+            title: "Draft".to_string(),
+            version: Defaultable::Default,
+        }
+        .insert(pool)
+        .await
+        .expect("Failed to insert document")
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_increment_the_version(pool: PgPool) {
+        let document = insert_document(&pool).await;
+
+        let updated = UpdatableDocument {
+            title: "Final".to_string(),
+            ..document.clone().into()
+        }
+        .update(&pool)
+        .await
+        .expect("Failed to update document");
+
+        check!(updated.title == "Final");
+        check!(updated.version == document.version + 1);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_reject_a_stale_version(pool: PgPool) {
+        let document = insert_document(&pool).await;
+
+        UpdatableDocument {
+            title: "First".to_string(),
+            ..document.clone().into()
+        }
+        .update(&pool)
+        .await
+        .expect("Failed to update document");
+
+        let result = UpdatableDocument {
+            title: "Second".to_string(),
+            ..document.clone().into()
+        }
+        .update(&pool)
+        .await;
+
+        assert2::let_assert!(Err(Error::StaleEntity) = result);
+
+        let document = DocumentPk::from(document)
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch document")
+            .expect("Could not find document");
+
+        check!(document.title == "First");
+        check!(document.version == 2);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_reject_a_missing_entity(pool: PgPool) {
+        let result = UpdatableDocument {
+            id: 1,
+            title: "Missing".to_string(),
+            version: 1,
+        }
+        .update(&pool)
+        .await;
+
+        assert2::let_assert!(Err(Error::StaleEntity) = result);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_increment_the_version_on_upsert(pool: PgPool) {
+        let setting = InsertableSetting {
+            name: "theme".to_string(),
+            value: "light".to_string(),
+            version: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert setting");
+
+        let upserted = InsertableSetting {
+            name: "theme".to_string(),
+            value: "dark".to_string(),
+            version: Defaultable::Default,
+        }
+        .upsert(&pool)
+        .await
+        .expect("Failed to upsert setting");
+
+        check!(upserted.value == "dark");
+        check!(upserted.version == setting.version + 1);
+
+        let result = UpdatableSetting {
+            value: "system".to_string(),
+            ..setting.into()
+        }
+        .update(&pool)
+        .await;
+
+        assert2::let_assert!(Err(Error::StaleEntity) = result);
+    }
+}
+
+mod timestamps {
+    use super::*;
+
+    async fn insert_post(pool: &PgPool) -> Post {
+        InsertablePost {
+            title: "Draft".to_string(),
+        }
+        .insert(pool)
+        .await
+        .expect("Failed to insert post")
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_set_the_timestamps_on_insert(pool: PgPool) {
+        let post = insert_post(&pool).await;
+
+        check!(post.created_at == post.updated_at);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_set_the_timestamps_on_insert_many(pool: PgPool) {
+        let posts = InsertablePost::insert_many(
+            &[
+                InsertablePost {
+                    title: "First".to_string(),
+                },
+                InsertablePost {
+                    title: "Second".to_string(),
+                },
+            ],
+            &pool,
+        )
+        .await
+        .expect("Failed to insert posts");
+
+        check!(posts.len() == 2);
+        check!(posts.iter().all(|post| post.created_at == post.updated_at));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_touch_updated_at_on_update(pool: PgPool) {
+        let post = insert_post(&pool).await;
+
+        let updated = UpdatablePost {
+            title: "Final".to_string(),
+            ..post.clone().into()
+        }
+        .update(&pool)
+        .await
+        .expect("Failed to update post");
+
+        check!(updated.title == "Final");
+        check!(updated.created_at == post.created_at);
+        check!(updated.updated_at > post.updated_at);
+    }
+}
+
+mod soft_delete {
+    use gremlin_orm::{Defaultable, Error, PurgeableEntity, QueryableEntity, SoftDeletableEntity};
+
+    use super::*;
+
+    async fn insert_deleted(pool: &PgPool, value: i32) -> SoftDelete {
+        let entity = InsertableSoftDelete {
+            value,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(pool)
+        .await
+        .expect("Failed to insert entity");
+
+        entity.delete(pool).await.expect("Failed to delete entity");
+
+        entity
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_restore_a_deleted_entity(pool: PgPool) {
+        let entity = insert_deleted(&pool, 1).await;
+
+        let restored = entity.restore(&pool).await.expect("Failed to restore");
+
+        check!(restored == entity);
+
+        let fetched = SoftDeletePk::from(&entity)
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch entity");
+
+        check!(fetched == Some(entity));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_not_restore_an_entity_which_is_not_deleted(pool: PgPool) {
+        let entity = InsertableUncheckedSoftDelete {
+            value: 1,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let result = entity.restore(&pool).await;
+
+        assert2::let_assert!(Err(Error::NotFound) = result);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_force_delete_an_entity(pool: PgPool) {
+        let entity = insert_deleted(&pool, 1).await;
+
+        entity.force_delete(&pool).await.expect("Failed to delete");
+
+        let fetched = SoftDelete::fetch_with_deleted(&SoftDeletePk::from(&entity), &pool)
+            .await
+            .expect("Failed to fetch entity");
+
+        check!(fetched == None);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_fetch_deleted_entities(pool: PgPool) {
+        let entity = insert_deleted(&pool, 1).await;
+
+        let fetched = SoftDelete::fetch_with_deleted(&SoftDeletePk::from(&entity), &pool)
+            .await
+            .expect("Failed to fetch entity")
+            .expect("Could not find entity");
+
+        check!(fetched.id == entity.id);
+        check!(fetched.deleted_at.is_some());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_stream_deleted_entities(pool: PgPool) {
+        let deleted = insert_deleted(&pool, 1).await;
+
+        let entity = InsertableUncheckedSoftDelete {
+            value: 2,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let mut all = UncheckedSoftDelete::stream_with_deleted(&pool)
+            .map(|entity| entity.expect("Failed to stream entity").id)
+            .collect::<Vec<_>>()
+            .await;
+
+        all.sort();
+
+        check!(all == vec![deleted.id, entity.id]);
+
+        let only = UncheckedSoftDelete::stream_only_deleted(&pool)
+            .map(|entity| entity.expect("Failed to stream entity").id)
+            .collect::<Vec<_>>()
+            .await;
+
+        check!(only == vec![deleted.id]);
+
+        let count = UncheckedSoftDelete::query()
+            .only_deleted()
+            .count(&pool)
+            .await
+            .expect("Failed to count entities");
+
+        check!(count == 1);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_purge_entities_deleted_before_a_timestamp(pool: PgPool) {
+        let entity = insert_deleted(&pool, 1).await;
+
+        let fetched = SoftDelete::fetch_with_deleted(&SoftDeletePk::from(&entity), &pool)
+            .await
+            .expect("Failed to fetch entity")
+            .expect("Could not find entity");
+
+        let deleted_at = fetched.deleted_at.expect("Entity is not deleted");
+
+        let purged = SoftDelete::purge_deleted_before(&deleted_at, &pool)
+            .await
+            .expect("Failed to purge");
+
+        check!(purged == 0);
+
+        let purged =
+            SoftDelete::purge_deleted_before(&(deleted_at + chrono::Duration::seconds(1)), &pool)
+                .await
+                .expect("Failed to purge");
+
+        check!(purged == 1);
+
+        let count = SoftDelete::query()
+            .with_deleted()
+            .count(&pool)
+            .await
+            .expect("Failed to count entities");
+
+        check!(count == 0);
+    }
+}
+
+mod soft_delete_kind {
+    use gremlin_orm::{Defaultable, QueryableEntity, SoftDeletableEntity};
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_soft_delete_using_a_flag(pool: PgPool) {
+        let entities = InsertableFlagSoftDelete::insert_many(
+            &[
+                InsertableFlagSoftDelete {
+                    value: 1,
+                    is_deleted: Defaultable::Default,
+                },
+                InsertableFlagSoftDelete {
+                    value: 2,
+                    is_deleted: Defaultable::Default,
+                },
+            ],
+            &pool,
+        )
+        .await
+        .expect("Failed to insert entities");
+
+        entities[0].delete(&pool).await.expect("Failed to delete");
+
+        let fetched = FlagSoftDeletePk::from(&entities[0])
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch entity");
+
+        check!(fetched == None);
+
+        let streamed = FlagSoftDelete::stream(&pool)
+            .map(|entity| entity.expect("Failed to stream entity"))
+            .collect::<Vec<_>>()
+            .await;
+
+        check!(streamed == vec![entities[1].clone()]);
+
+        let deleted = FlagSoftDelete::query()
+            .only_deleted()
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query entities");
+
+        check!(deleted.len() == 1);
+        check!(deleted[0].is_deleted);
+
+        let restored = entities[0].restore(&pool).await.expect("Failed to restore");
+
+        check!(restored == entities[0]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_soft_delete_using_custom_expressions(pool: PgPool) {
+        let entity = InsertableStatusSoftDelete {
+            value: 1,
+            status: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        check!(entity.status == "active");
+
+        entity.delete(&pool).await.expect("Failed to delete");
+
+        let fetched = StatusSoftDeletePk::from(&entity)
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch entity");
+
+        check!(fetched == None);
+
+        let result = UpdatableStatusSoftDelete {
+            id: entity.id,
+            value: 2,
+        }
+        .update(&pool)
+        .await;
+
+        assert2::let_assert!(Err(gremlin_orm::Error::NotFound) = result);
+
+        let deleted =
+            StatusSoftDelete::fetch_with_deleted(&StatusSoftDeletePk::from(&entity), &pool)
+                .await
+                .expect("Failed to fetch entity")
+                .expect("Could not find entity");
+
+        check!(deleted.status == "deleted");
+
+        let restored = entity.restore(&pool).await.expect("Failed to restore");
+
+        check!(restored == entity);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_purge_deleted_entities(pool: PgPool) {
+        let entities = InsertableStatusSoftDelete::insert_many(
+            &[
+                InsertableStatusSoftDelete {
+                    value: 1,
+                    status: Defaultable::Default,
+                },
+                InsertableStatusSoftDelete {
+                    value: 2,
+                    status: Defaultable::Default,
+                },
+            ],
+            &pool,
+        )
+        .await
+        .expect("Failed to insert entities");
+
+        entities[0].delete(&pool).await.expect("Failed to delete");
+
+        let purged = StatusSoftDelete::purge_deleted(&pool)
+            .await
+            .expect("Failed to purge");
+
+        check!(purged == 1);
+
+        let count = StatusSoftDelete::query()
+            .with_deleted()
+            .count(&pool)
+            .await
+            .expect("Failed to count entities");
+
+        check!(count == 1);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_treat_null_custom_markers_as_deleted(pool: PgPool) {
+        let entity = InsertableNullableStatusSoftDelete {
+            value: 1,
+            status: Defaultable::Value(None),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let live = NullableStatusSoftDelete::query()
+            .count(&pool)
+            .await
+            .expect("Failed to count entities");
+
+        let deleted = NullableStatusSoftDelete::query()
+            .only_deleted()
+            .count(&pool)
+            .await
+            .expect("Failed to count entities");
+
+        check!(live == 0);
+        check!(deleted == 1);
+
+        let restored = entity.restore(&pool).await.expect("Failed to restore");
+
+        check!(restored.status == Some("active".to_string()));
+    }
+}
+
+mod patch {
+    use gremlin_orm::{Defaultable, Error, Nullable, PatchableEntity};
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_only_update_the_present_fields(pool: PgPool) {
+        let release = InsertableRelease {
+            name: "Testings".to_string(),
+            synonyms: Some(vec!["Tests".to_string()]),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert release");
+
+        let patched = PatchRelease {
+            name: Some("Updated".to_string()),
+            ..ReleasePk::from(&release).into()
+        }
+        .patch(&pool)
+        .await
+        .expect("Failed to patch release");
+
+        check!(patched.name == "Updated");
+        check!(patched.slug == "updated");
+        check!(patched.synonyms == release.synonyms);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_set_nullable_fields(pool: PgPool) {
+        let release = insert_release(&pool, "Testings").await;
+
+        let patched = PatchRelease {
+            id: release.id,
+            name: None,
+            synonyms: Nullable::Value(vec!["Other".to_string()]),
+        }
+        .patch(&pool)
+        .await
+        .expect("Failed to patch release");
+
+        check!(patched.name == release.name);
+        check!(patched.synonyms == Some(vec!["Other".to_string()]));
+
+        let patched = PatchRelease {
+            synonyms: Nullable::Null,
+            ..ReleasePk::from(&release).into()
+        }
+        .patch(&pool)
+        .await
+        .expect("Failed to patch release");
+
+        check!(patched.synonyms == None);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_return_the_entity_for_an_empty_patch(pool: PgPool) {
+        let release = insert_release(&pool, "Testings").await;
+
+        let patched = PatchRelease::from(ReleasePk::from(&release))
+            .patch(&pool)
+            .await
+            .expect("Failed to patch release");
+
+        check!(patched == release);
+
+        let result = PatchRelease::from(ReleasePk { id: release.id + 1 })
+            .patch(&pool)
+            .await;
+
+        assert2::let_assert!(Err(Error::NotFound) = result);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_check_the_version(pool: PgPool) {
+        let document = InsertableDocument {
+            title: "Draft".to_string(),
+            version: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert document");
+
+        let patch = PatchDocument {
+            id: document.id,
+            version: document.version,
+            title: Some("Final".to_string()),
+        };
+
+        let patched = patch.patch(&pool).await.expect("Failed to patch document");
+
+        check!(patched.title == "Final");
+        check!(patched.version == document.version + 1);
+
+        let result = patch.patch(&pool).await;
+
+        assert2::let_assert!(Err(Error::StaleEntity) = result);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_touch_updated_at(pool: PgPool) {
+        let post = InsertablePost {
+            title: "Draft".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert post");
+
+        let patched = PatchPost {
+            title: Some("Published".to_string()),
+            ..PostPk::from(&post).into()
+        }
+        .patch(&pool)
+        .await
+        .expect("Failed to patch post");
+
+        check!(patched.title == "Published");
+        check!(patched.updated_at > post.updated_at);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_not_touch_the_entity_for_an_empty_patch(pool: PgPool) {
+        let document = InsertableDocument {
+            title: "Draft".to_string(),
+            version: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert document");
+
+        let patched = PatchDocument {
+            id: document.id,
+            version: document.version,
+            title: None,
+        }
+        .patch(&pool)
+        .await
+        .expect("Failed to patch document");
+
+        check!(patched == document);
+
+        let post = InsertablePost {
+            title: "Draft".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert post");
+
+        let patched = PatchPost::from(PostPk::from(&post))
+            .patch(&pool)
+            .await
+            .expect("Failed to patch post");
+
+        check!(patched == post);
+    }
+}
+
+mod try_update {
+    use gremlin_orm::Defaultable;
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_return_the_updated_entity(pool: PgPool) {
+        let artist = InsertableArtist {
+            name: "Testings".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        let updated = UpdatableArtist {
+            id: artist.id,
+            name: "Updated".to_string(),
+        }
+        .try_update(&pool)
+        .await
+        .expect("Failed to update artist");
+
+        check!(updated.map(|artist| artist.name) == Some("Updated".to_string()));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_return_none_for_a_missing_entity(pool: PgPool) {
+        let updated = UpdatableArtist {
+            id: 1,
+            name: "Updated".to_string(),
+        }
+        .try_update(&pool)
+        .await
+        .expect("Failed to update artist");
+
+        check!(updated == None);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_return_none_for_a_soft_deleted_entity(pool: PgPool) {
+        let entity = InsertableUncheckedSoftDelete {
+            value: 0,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        entity.delete(&pool).await.expect("Failed to delete entity");
+
+        let updated = UpdatableUncheckedSoftDelete {
+            id: entity.id,
+            value: 1,
+        }
+        .try_update(&pool)
+        .await
+        .expect("Failed to update entity");
+
+        check!(updated == None);
+    }
+}
+
+mod try_delete {
+    use gremlin_orm::Defaultable;
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_report_whether_the_entity_was_deleted(pool: PgPool) {
+        let artist = InsertableArtist {
+            name: "Testings".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        let first = artist.try_delete(&pool).await.expect("Failed to delete");
+        let second = artist.try_delete(&pool).await.expect("Failed to delete");
+
+        check!(first);
+        check!(!second);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_report_whether_the_entity_was_soft_deleted(pool: PgPool) {
+        let entity = InsertableSoftDelete {
+            value: 0,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let first = entity.try_delete(&pool).await.expect("Failed to delete");
+        let second = entity.try_delete(&pool).await.expect("Failed to delete");
+
+        check!(first);
+        check!(!second);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_return_the_deleted_entity(pool: PgPool) {
+        let artist = InsertableArtist {
+            name: "Testings".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        let deleted = artist
+            .delete_returning(&pool)
+            .await
+            .expect("Failed to delete");
+
+        check!(deleted.as_ref() == Some(&artist));
+
+        let deleted = artist
+            .delete_returning(&pool)
+            .await
+            .expect("Failed to delete");
+
+        check!(deleted == None);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_return_the_soft_deleted_entity(pool: PgPool) {
+        let entity = InsertableUncheckedSoftDelete {
+            value: 0,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let deleted = entity
+            .delete_returning(&pool)
+            .await
+            .expect("Failed to delete")
+            .expect("Entity was not deleted");
+
+        check!(deleted.id == entity.id);
+        check!(deleted.deleted_at.is_some());
+    }
+}
+
+mod update_expression {
+    use gremlin_orm::{Defaultable, QueryableEntity};
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_increment_and_decrement_columns(pool: PgPool) {
+        let entity = InsertableSoftDelete {
+            value: 10,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let columns = SoftDelete::columns();
+
+        let incremented = SoftDelete::update_where(SoftDeletePk { id: entity.id })
+            .increment(columns.value, 5)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to increment value");
+
+        let decremented = SoftDelete::update_where(SoftDeletePk { id: entity.id })
+            .decrement(columns.value, 3)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to decrement value");
+
+        check!(incremented.value == 15);
+        check!(decremented.value == 12);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_append_to_arrays_and_set_columns(pool: PgPool) {
+        let release = InsertableRelease {
+            name: "Testings".to_string(),
+            synonyms: None,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert release");
+
+        let columns = Release::columns();
+
+        let updated = Release::update_where(ReleasePk { id: release.id })
+            .append(columns.synonyms, "First")
+            .set(columns.name, "Updated")
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to update release");
+
+        let appended = Release::update_where(ReleasePk { id: release.id })
+            .append(columns.synonyms, "Second")
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to update release");
+
+        check!(updated.name == "Updated");
+        check!(updated.synonyms == Some(vec!["First".to_string()]));
+        check!(appended.synonyms == Some(vec!["First".to_string(), "Second".to_string()]));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_update_all_matching_entities(pool: PgPool) {
+        for value in [1, 2, 3] {
+            InsertableSoftDelete {
+                value,
+                deleted_at: Defaultable::Default,
+            }
+            .insert(&pool)
+            .await
+            .expect("Failed to insert entity");
+        }
+
+        let columns = SoftDelete::columns();
+
+        let mut updated = SoftDelete::update_where(columns.value.ge(2))
+            .increment(columns.value, 10)
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to update entities")
+            .into_iter()
+            .map(|entity| entity.value)
+            .collect::<Vec<_>>();
+
+        updated.sort();
+
+        check!(updated == vec![12, 13]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_not_update_soft_deleted_entities(pool: PgPool) {
+        let entity = InsertableSoftDelete {
+            value: 0,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        entity.delete(&pool).await.expect("Failed to delete entity");
+
+        let columns = SoftDelete::columns();
+
+        let updated = SoftDelete::update_where(SoftDeletePk { id: entity.id })
+            .increment(columns.value, 1)
+            .fetch_optional(&pool)
+            .await
+            .expect("Failed to update entity");
+
+        check!(updated == None);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_increment_the_version(pool: PgPool) {
+        let document = InsertableDocument {
+            title: "Draft".to_string(),
+            version: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert document");
+
+        let columns = Document::columns();
+
+        let updated = Document::update_where(DocumentPk { id: document.id })
+            .set(columns.title, "Final")
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to update document");
+
+        check!(updated.title == "Final");
+        check!(updated.version == document.version + 1);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_set_the_version(pool: PgPool) {
+        let document = InsertableDocument {
+            title: "Draft".to_string(),
+            version: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert document");
+
+        let columns = Document::columns();
+
+        let updated = Document::update_where(DocumentPk { id: document.id })
+            .set(columns.version, 10)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to update document");
+
+        check!(updated.version == 10);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_set_the_updated_at_timestamp(pool: PgPool) {
+        let post = InsertablePost {
+            title: "Draft".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert post");
+
+        let columns = Post::columns();
+
+        let updated = Post::update_where(PostPk { id: post.id })
+            .set(columns.title, "Imported")
+            .set(columns.updated_at, NaiveDateTime::default())
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to update post");
+
+        check!(updated.title == "Imported");
+        check!(updated.updated_at == NaiveDateTime::default());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_return_not_found_for_a_missing_entity(pool: PgPool) {
+        let columns = Artist::columns();
+
+        let result = Artist::update_where(ArtistPk { id: 1 })
+            .set(columns.name, "Updated")
+            .fetch_one(&pool)
+            .await;
+
+        assert2::let_assert!(Err(gremlin_orm::Error::NotFound) = result);
+    }
+}
+
+mod update_if {
+    use gremlin_orm::{Defaultable, QueryableEntity, RawSql};
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_update_the_entity_when_the_guard_matches(pool: PgPool) {
+        let artist = InsertableArtist {
+            name: "Pending".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        let columns = Artist::columns();
+
+        let updated = UpdatableArtist {
+            id: artist.id,
+            name: "Running".to_string(),
+        }
+        .update_if(columns.name.eq("Pending"), &pool)
+        .await
+        .expect("Failed to update artist");
+
+        check!(updated.map(|artist| artist.name) == Some("Running".to_string()));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_return_none_when_the_guard_does_not_match(pool: PgPool) {
+        let artist = InsertableArtist {
+            name: "Done".to_string(),
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert artist");
+
+        let columns = Artist::columns();
+
+        let updated = UpdatableArtist {
+            id: artist.id,
+            name: "Running".to_string(),
+        }
+        .update_if(columns.name.eq("Pending"), &pool)
+        .await
+        .expect("Failed to update artist");
+
+        let stored = ArtistPk { id: artist.id }
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch artist");
+
+        check!(updated == None);
+        check!(stored.map(|artist| artist.name) == Some("Done".to_string()));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_support_raw_sql_guards(pool: PgPool) {
+        let entity = InsertableUncheckedSoftDelete {
+            value: 5,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let updatable = UpdatableUncheckedSoftDelete {
+            id: entity.id,
+            value: 6,
+        };
+
+        let missed = updatable
+            .update_if(RawSql::new("value > $1 OR value = $1").bind(10), &pool)
+            .await
+            .expect("Failed to update entity");
+
+        let updated = updatable
+            .update_if(
+                RawSql::new("value BETWEEN $1 AND $2").bind(0).bind(5),
+                &pool,
+            )
+            .await
+            .expect("Failed to update entity");
+
+        check!(missed == None);
+        check!(updated.map(|entity| entity.value) == Some(6));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_not_renumber_quoted_placeholders(pool: PgPool) {
+        let entity = InsertableUncheckedSoftDelete {
+            value: 5,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let guard = RawSql::new(
+            "value = $1 AND '$1' = $2 AND $$$1$$ = $2 AND $tag$$2$tag$ = '$' || '2' /* $3 */",
+        )
+        .bind(5)
+        .bind("$1");
+
+        let updated = UpdatableUncheckedSoftDelete {
+            id: entity.id,
+            value: 6,
+        }
+        .update_if(guard, &pool)
+        .await
+        .expect("Failed to update entity");
+
+        check!(updated.map(|entity| entity.value) == Some(6));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_not_renumber_placeholders_in_escape_strings(pool: PgPool) {
+        let entity = InsertableUncheckedSoftDelete {
+            value: 5,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let guard = RawSql::new(r"value = $1 AND E'it\'s $1' = 'it''s ' || '$' || '1'").bind(5);
+
+        let updated = UpdatableUncheckedSoftDelete {
+            id: entity.id,
+            value: 6,
+        }
+        .update_if(guard, &pool)
+        .await
+        .expect("Failed to update entity");
+
+        check!(updated.map(|entity| entity.value) == Some(6));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_reject_unbound_placeholders(pool: PgPool) {
+        let entity = InsertableUncheckedSoftDelete {
+            value: 5,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        let result = UpdatableUncheckedSoftDelete {
+            id: entity.id,
+            value: 6,
+        }
+        .update_if(RawSql::new("value = $2").bind(5), &pool)
+        .await;
+
+        assert2::let_assert!(Err(gremlin_orm::Error::Database(sqlx::Error::Encode(_))) = result);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_not_update_soft_deleted_entities(pool: PgPool) {
+        let entity = InsertableSoftDelete {
+            value: 0,
+            deleted_at: Defaultable::Default,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert entity");
+
+        entity.delete(&pool).await.expect("Failed to delete entity");
+
+        let columns = SoftDelete::columns();
+
+        let updated = UpdatableSoftDelete {
+            id: entity.id,
+            value: 1,
+        }
+        .update_if(columns.value.eq(0), &pool)
+        .await
+        .expect("Failed to update entity");
+
+        check!(updated == None);
+    }
+}
+
+mod bulk {
+    use gremlin_orm::{PageableEntity, QueryableEntity};
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_delete_many_by_primary_key(pool: PgPool) {
+        let artists = insert_artists(&pool, &["First", "Second", "Third"]).await;
+
+        let pks = artists[..2].iter().map(ArtistPk::from).collect::<Vec<_>>();
+
+        let deleted = Artist::delete_many(&pks)
+            .execute(&pool)
+            .await
+            .expect("Failed to delete artists");
+
+        let remaining = Artist::query()
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to fetch artists");
+
+        check!(deleted == 2);
+        check!(remaining == vec![artists[2].clone()]);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_delete_many_by_composite_primary_keys(pool: PgPool) {
+        let artists = insert_artists(&pool, &["First", "Second"]).await;
+
+        let release = InsertableRelease {
+            name: "Testings".to_string(),
+            synonyms: None,
+        }
+        .insert(&pool)
+        .await
+        .expect("Failed to insert release");
+
+        for artist in &artists {
+            InsertableArtistRelease {
+                artist_id: artist.id,
+                release_id: release.id,
+            }
+            .insert(&pool)
+            .await
+            .expect("Failed to insert artist release");
+        }
+
+        let pks = vec![ArtistReleasePk {
+            artist_id: artists[0].id,
+            release_id: release.id,
+        }];
+
+        let deleted = ArtistRelease::delete_many(&pks)
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to delete artist releases");
+
+        let remaining = ArtistRelease::query()
+            .count(&pool)
+            .await
+            .expect("Failed to count artist releases");
+
+        check!(deleted.len() == 1);
+        check!(deleted[0].artist_id == artists[0].id);
+        check!(remaining == 1);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_delete_where_returning_the_entities(pool: PgPool) {
+        let artists = insert_artists(&pool, &["The First", "The Second", "Third"]).await;
+
+        let columns = Artist::columns();
+
+        let mut deleted = Artist::delete_where(columns.name.like("The %"))
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to delete artists");
+
+        deleted.sort_by_key(|artist| artist.id);
+
+        check!(deleted == artists[..2].to_vec());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_soft_delete_in_bulk(pool: PgPool) {
+        let entities = insert_soft_deletes(&pool, &[1, 2, 3]).await;
+
+        entities[0]
+            .delete(&pool)
+            .await
+            .expect("Failed to delete entity");
+
+        let columns = SoftDelete::columns();
+
+        let deleted = SoftDelete::delete_where(columns.value.le(2))
+            .execute(&pool)
+            .await
+            .expect("Failed to delete entities");
+
+        let remaining = SoftDelete::query()
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to fetch entities");
+
+        let stored = SoftDelete::query()
+            .with_deleted()
+            .count(&pool)
+            .await
+            .expect("Failed to count entities");
+
+        check!(deleted == 1);
+        check!(
+            remaining
+                .iter()
+                .map(|entity| entity.value)
+                .collect::<Vec<_>>()
+                == vec![3]
+        );
+        check!(stored == 3);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_update_where_returning_the_count(pool: PgPool) {
+        let entities = insert_soft_deletes(&pool, &[1, 2, 3]).await;
+
+        entities[2]
+            .delete(&pool)
+            .await
+            .expect("Failed to delete entity");
+
+        let columns = SoftDelete::columns();
+
+        let updated = SoftDelete::update_where(columns.value.ge(1))
+            .set(columns.value, 0)
+            .execute(&pool)
+            .await
+            .expect("Failed to update entities");
+
+        let deleted = SoftDelete::query()
+            .only_deleted()
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to fetch entities");
+
+        check!(updated == 2);
+        check!(
+            deleted
+                .iter()
+                .map(|entity| entity.value)
+                .collect::<Vec<_>>()
+                == vec![3]
+        );
+    }
+}
+
+mod lock {
+    use gremlin_orm::{Defaultable, Lock, LockableEntity, PageableEntity, QueryableEntity};
+
+    use super::*;
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_fetch_the_entity_for_update(pool: PgPool) {
+        let artists = insert_artists(&pool, &["Testings"]).await;
+
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+
+        let locked = ArtistPk { id: artists[0].id }
+            .fetch_for_update(&mut *tx)
+            .await
+            .expect("Failed to fetch artist");
+
+        let shared = ArtistPk { id: artists[0].id }
+            .fetch_for_share(&mut *tx)
+            .await
+            .expect("Failed to fetch artist");
+
+        tx.commit().await.expect("Failed to commit transaction");
+
+        check!(locked == Some(artists[0].clone()));
+        check!(shared == Some(artists[0].clone()));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_not_wait_for_locked_rows(pool: PgPool) {
+        let artists = insert_artists(&pool, &["Testings"]).await;
+        let pk = ArtistPk { id: artists[0].id };
+
+        let mut first = pool.begin().await.expect("Failed to begin transaction");
+        let mut second = pool.begin().await.expect("Failed to begin transaction");
+
+        pk.fetch_for_update(&mut *first)
+            .await
+            .expect("Failed to fetch artist");
+
+        let skipped = pk
+            .fetch_locked(Lock::update().skip_locked(), &mut *second)
+            .await
+            .expect("Failed to fetch artist");
+
+        let result = pk.fetch_locked(Lock::share().nowait(), &mut *second).await;
+
+        check!(skipped == None);
+        assert2::let_assert!(Err(gremlin_orm::Error::Database(_)) = result);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_claim_different_entities_concurrently(pool: PgPool) {
+        let artists = insert_artists(&pool, &["First", "Second", "Third"]).await;
+
+        let mut first = pool.begin().await.expect("Failed to begin transaction");
+        let mut second = pool.begin().await.expect("Failed to begin transaction");
+
+        let claimed_first = Artist::claim_next(2, &mut *first)
+            .await
+            .expect("Failed to claim artists");
+
+        let claimed_second = Artist::claim_next(2, &mut *second)
+            .await
+            .expect("Failed to claim artists");
+
+        check!(claimed_first == artists[..2].to_vec());
+        check!(claimed_second == artists[2..].to_vec());
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_reject_an_empty_claim_limit(pool: PgPool) {
+        insert_artists(&pool, &["First"]).await;
+
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+
+        let result = Artist::claim_next(0, &mut *tx).await;
+
+        assert2::let_assert!(Err(gremlin_orm::Error::InvalidPageLimit) = result);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_claim_with_the_maximum_limit(pool: PgPool) {
+        let artists = insert_artists(&pool, &["First", "Second"]).await;
+
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+
+        let claimed = Artist::claim_next(u64::MAX, &mut *tx)
+            .await
+            .expect("Failed to claim artists");
+
+        check!(claimed == artists);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_claim_entities_matching_the_query(pool: PgPool) {
+        for value in [1, 2, 3] {
+            InsertableSoftDelete {
+                value,
+                deleted_at: Defaultable::Default,
+            }
+            .insert(&pool)
+            .await
+            .expect("Failed to insert entity");
+        }
+
+        let columns = SoftDelete::columns();
+
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+
+        let claimed = SoftDelete::query()
+            .filter(columns.value.ge(2))
+            .order_by(columns.value.desc())
+            .claim_next(1, &mut *tx)
+            .await
+            .expect("Failed to claim entities");
+
+        check!(
+            claimed
+                .iter()
+                .map(|entity| entity.value)
+                .collect::<Vec<_>>()
+                == vec![3]
+        );
+    }
+}
+
+mod transaction {
+    use std::time::Duration;
+
+    use gremlin_orm::{Error, IsolationLevel, QueryableEntity, TransactionOptions};
+
+    use super::*;
+
+    async fn insert_artist(conn: &mut sqlx::PgConnection, name: &str) -> Result<Artist, Error> {
+        InsertableArtist {
+            name: name.to_string(),
+        }
+        .insert(conn)
+        .await
+    }
+
+    async fn count_artists(pool: &PgPool) -> i64 {
+        Artist::query()
+            .count(pool)
+            .await
+            .expect("Failed to count artists")
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_commit_when_the_closure_succeeds(pool: PgPool) {
+        let artist = gremlin_orm::transaction(&pool, IsolationLevel::Serializable, async |conn| {
+            insert_artist(conn, "Testings").await
+        })
+        .await
+        .expect("Failed to run transaction");
+
+        let stored = ArtistPk { id: artist.id }
+            .fetch(&pool)
+            .await
+            .expect("Failed to fetch artist");
+
+        check!(stored == Some(artist));
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_roll_back_when_the_closure_fails(pool: PgPool) {
+        let result = gremlin_orm::transaction(&pool, IsolationLevel::ReadCommitted, async |conn| {
+            insert_artist(conn, "Testings").await?;
+
+            Err::<(), _>(Error::NotFound)
+        })
+        .await;
+
+        assert2::let_assert!(Err(Error::NotFound) = result);
+        check!(count_artists(&pool).await == 0);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_retry_serialization_failures(pool: PgPool) {
+        let options = TransactionOptions::new(IsolationLevel::Serializable)
+            .backoff(Duration::from_millis(1), Duration::from_millis(5));
+
+        let mut attempts = 0;
+
+        let artist = gremlin_orm::transaction(&pool, options, async |conn| {
+            attempts += 1;
+
+            let artist = insert_artist(conn, "Testings").await?;
+
+            if attempts < 3 {
+                return Err(Error::SerializationFailure(sqlx::Error::Protocol(
+                    "Conflict".to_string(),
+                )));
+            }
+
+            Ok(artist)
+        })
+        .await
+        .expect("Failed to run transaction");
+
+        check!(attempts == 3);
+        check!(artist.name == "Testings");
+        check!(count_artists(&pool).await == 1);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_give_up_after_the_retries(pool: PgPool) {
+        let options = TransactionOptions::new(IsolationLevel::Serializable)
+            .retries(2)
+            .backoff(Duration::from_millis(1), Duration::from_millis(1));
+
+        let mut attempts = 0;
+
+        let result = gremlin_orm::transaction(&pool, options, async |_conn| {
+            attempts += 1;
+
+            Err::<(), _>(Error::Deadlock(sqlx::Error::Protocol(
+                "Deadlock".to_string(),
+            )))
+        })
+        .await;
+
+        assert2::let_assert!(Err(Error::Deadlock(_)) = result);
+        check!(attempts == 3);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_reject_writes_in_read_only_transactions(pool: PgPool) {
+        let options = TransactionOptions::new(IsolationLevel::Serializable)
+            .read_only()
+            .deferrable();
+
+        let result = gremlin_orm::transaction(&pool, options, async |conn| {
+            insert_artist(conn, "Testings").await
+        })
+        .await;
+
+        assert2::let_assert!(Err(Error::Database(_)) = result);
+        check!(count_artists(&pool).await == 0);
+    }
+
+    #[sqlx::test(fixtures("../resources/data/schema.sql"))]
+    async fn it_should_roll_back_failed_savepoints(pool: PgPool) {
+        let names = gremlin_orm::transaction(&pool, IsolationLevel::ReadCommitted, async |conn| {
+            insert_artist(conn, "Outer").await?;
+
+            let inner = gremlin_orm::savepoint(&mut *conn, async |conn| {
+                insert_artist(conn, "Inner").await?;
+
+                Err::<(), _>(Error::NotFound)
+            })
+            .await;
+
+            assert2::let_assert!(Err(Error::NotFound) = inner);
+
+            gremlin_orm::savepoint(&mut *conn, async |conn| insert_artist(conn, "Nested").await)
+                .await?;
+
+            let artists = Artist::query()
+                .order_by(Artist::columns().id.asc())
+                .fetch_all(&mut *conn)
+                .await?;
+
+            Ok(artists
+                .into_iter()
+                .map(|artist| artist.name)
+                .collect::<Vec<_>>())
+        })
+        .await
+        .expect("Failed to run transaction");
+
+        check!(names == vec!["Outer".to_string(), "Nested".to_string()]);
+        check!(count_artists(&pool).await == 2);
+    }
+}